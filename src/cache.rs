@@ -0,0 +1,342 @@
+//! On-disk cache of fetched calendars.
+//!
+//! Entries are keyed by source, user and date range. Fresh entries are served as is; stale ones
+//! are revalidated with the `ETag` and `Last-Modified` the server sent along with them.
+
+use crate::calendar::{ContributionCalendar, DateRange};
+use crate::error::{ContriviewError, Result};
+use crate::source::{ContributionSource, Fetched, Validators};
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How long an entry is served without asking the server, unless `--cache-ttl` says otherwise.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// How [`CachedSource`] uses the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CacheMode {
+    /// Serves fresh entries and revalidates stale ones.
+    #[default]
+    Normal,
+    /// Serves entries whatever their age and never touches the network.
+    Offline,
+    /// Ignores cached entries, fetches again and stores the result.
+    Refresh,
+}
+
+/// A directory of cached calendars.
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: PathBuf,
+    ttl: Duration,
+    mode: CacheMode,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    fetched_at: DateTime<Utc>,
+    validators: Validators,
+    calendar: ContributionCalendar,
+}
+
+impl Cache {
+    /// A cache stored in `dir`, created on first write.
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Cache {
+            dir: dir.into(),
+            ttl: DEFAULT_TTL,
+            mode: CacheMode::default(),
+        }
+    }
+
+    /// `contriview` under the platform cache directory, e.g. `~/.cache/contriview`.
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("contriview"))
+    }
+
+    /// Serves entries younger than `ttl` without revalidating them.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Uses the cache as `mode` says.
+    pub fn with_mode(mut self, mode: CacheMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Directory holding the entries.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+
+    /// Reads the entry of `key`. Missing and unreadable entries are both a miss.
+    fn load(&self, key: &str) -> Option<Entry> {
+        let json = fs::read_to_string(self.path(key)).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    /// Writes the entry of `key`. Failures are ignored, as they only cost the next run a request.
+    fn store(&self, key: &str, entry: &Entry) {
+        if let Ok(json) = serde_json::to_string(entry) {
+            let _ = fs::create_dir_all(&self.dir).and_then(|_| fs::write(self.path(key), json));
+        }
+    }
+
+    fn is_fresh(&self, entry: &Entry) -> bool {
+        let age = Utc::now().signed_duration_since(entry.fetched_at);
+        age.to_std().is_ok_and(|age| age < self.ttl)
+    }
+}
+
+/// File name of the entry for `username` and `range` fetched from the source `id`.
+///
+/// Characters that are not safe in file names are percent-encoded, so distinct keys never
+/// share a file.
+fn cache_key(id: &str, username: &str, range: &DateRange) -> String {
+    let bound = |date: Option<NaiveDate>| date.map(|d| d.to_string()).unwrap_or_default();
+    let key = format!(
+        "{} {} {}..{}",
+        id,
+        username,
+        bound(range.from),
+        bound(range.to)
+    );
+
+    key.bytes()
+        .map(|b| match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Wraps another source, answering from a [`Cache`] when it can.
+///
+/// Sources without a [`cache_id`](ContributionSource::cache_id) are passed through untouched.
+pub struct CachedSource {
+    inner: Box<dyn ContributionSource>,
+    cache: Cache,
+}
+
+impl CachedSource {
+    /// Caches the calendars fetched by `inner` in `cache`.
+    pub fn new(inner: Box<dyn ContributionSource>, cache: Cache) -> Self {
+        CachedSource { inner, cache }
+    }
+}
+
+impl ContributionSource for CachedSource {
+    fn fetch(&self, username: &str, range: &DateRange) -> Result<ContributionCalendar> {
+        let id = match self.inner.cache_id() {
+            Some(id) => id,
+            None => return self.inner.fetch(username, range),
+        };
+        let key = cache_key(&id, username, range);
+
+        let cached = match self.cache.mode {
+            CacheMode::Refresh => None,
+            _ => self.cache.load(&key),
+        };
+        if let Some(entry) = &cached {
+            if self.cache.mode == CacheMode::Offline || self.cache.is_fresh(entry) {
+                return Ok(entry.calendar.clone());
+            }
+        }
+        if self.cache.mode == CacheMode::Offline {
+            return Err(ContriviewError::NotCached(username.to_string()));
+        }
+
+        let validators = cached
+            .as_ref()
+            .map(|entry| entry.validators.clone())
+            .unwrap_or_default();
+        let (calendar, validators) =
+            match self.inner.fetch_if_modified(username, range, &validators)? {
+                Fetched::Modified(calendar, validators) => (calendar, validators),
+                Fetched::NotModified => match cached {
+                    Some(entry) => (entry.calendar, entry.validators),
+                    None => {
+                        return Err(ContriviewError::Api(format!(
+                            "{} answered 304 to an unconditional request",
+                            id
+                        )))
+                    }
+                },
+            };
+
+        let entry = Entry {
+            fetched_at: Utc::now(),
+            validators,
+            calendar,
+        };
+        self.cache.store(&key, &entry);
+
+        Ok(entry.calendar)
+    }
+
+    fn cache_id(&self) -> Option<String> {
+        self.inner.cache_id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::GithubHtmlSource;
+    use crate::fixtures::{table_html, TempDir};
+    use crate::mock_server::{MockServer, Response};
+    use crate::source::RecordedSource;
+
+    /// Answers 304 to requests carrying the current ETag, and the table fixture otherwise.
+    fn etag_server() -> MockServer {
+        MockServer::start(|request| {
+            if request.header("if-none-match") == Some("\"v1\"") {
+                Response::new(304, "")
+            } else {
+                Response::new(200, table_html())
+                    .header("ETag", "\"v1\"")
+                    .header("Last-Modified", "Sun, 15 Jan 2023 00:00:00 GMT")
+            }
+        })
+    }
+
+    fn cached(server: &MockServer, cache: Cache) -> CachedSource {
+        CachedSource::new(
+            Box::new(GithubHtmlSource::new().with_host(server.url())),
+            cache,
+        )
+    }
+
+    #[test]
+    fn test_fresh_entry_is_served_without_request() {
+        let dir = TempDir::new("cache-fresh");
+        let server = etag_server();
+        let source = cached(&server, Cache::new(dir.path()));
+
+        let first = source.fetch("k-nasa", &DateRange::default()).unwrap();
+        let second = source.fetch("k-nasa", &DateRange::default()).unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(14, second.days().len());
+        assert_eq!(1, server.requests().len());
+    }
+
+    #[test]
+    fn test_stale_entry_is_revalidated() {
+        let dir = TempDir::new("cache-stale");
+        let server = etag_server();
+        let source = cached(&server, Cache::new(dir.path()).with_ttl(Duration::ZERO));
+
+        let first = source.fetch("k-nasa", &DateRange::default()).unwrap();
+        let second = source.fetch("k-nasa", &DateRange::default()).unwrap();
+
+        assert_eq!(first, second);
+        let requests = server.requests();
+        assert_eq!(2, requests.len());
+        assert_eq!(None, requests[0].header("if-none-match"));
+        assert_eq!(Some("\"v1\""), requests[1].header("if-none-match"));
+        assert_eq!(
+            Some("Sun, 15 Jan 2023 00:00:00 GMT"),
+            requests[1].header("if-modified-since")
+        );
+    }
+
+    #[test]
+    fn test_refresh_ignores_entry() {
+        let dir = TempDir::new("cache-refresh");
+        let server = etag_server();
+        cached(&server, Cache::new(dir.path()))
+            .fetch("k-nasa", &DateRange::default())
+            .unwrap();
+
+        let source = cached(
+            &server,
+            Cache::new(dir.path()).with_mode(CacheMode::Refresh),
+        );
+        source.fetch("k-nasa", &DateRange::default()).unwrap();
+
+        let requests = server.requests();
+        assert_eq!(2, requests.len());
+        assert_eq!(None, requests[1].header("if-none-match"));
+    }
+
+    #[test]
+    fn test_offline() {
+        let dir = TempDir::new("cache-offline");
+        let server = etag_server();
+        let offline = cached(
+            &server,
+            Cache::new(dir.path())
+                .with_ttl(Duration::ZERO)
+                .with_mode(CacheMode::Offline),
+        );
+
+        assert!(matches!(
+            offline.fetch("k-nasa", &DateRange::default()),
+            Err(ContriviewError::NotCached(u)) if u == "k-nasa"
+        ));
+
+        let fetched = cached(&server, Cache::new(dir.path()))
+            .fetch("k-nasa", &DateRange::default())
+            .unwrap();
+
+        assert_eq!(
+            fetched,
+            offline.fetch("k-nasa", &DateRange::default()).unwrap()
+        );
+        assert_eq!(1, server.requests().len());
+    }
+
+    #[test]
+    fn test_unwritable_cache() {
+        let dir = TempDir::new("cache-unwritable");
+        let cache_dir = dir.path().join("cache");
+        std::fs::write(&cache_dir, "").unwrap();
+        let server = etag_server();
+        let source = cached(&server, Cache::new(&cache_dir));
+
+        let calendar = source.fetch("k-nasa", &DateRange::default()).unwrap();
+
+        assert_eq!(14, calendar.days().len());
+    }
+
+    #[test]
+    fn test_local_sources_are_not_cached() {
+        let dir = TempDir::new("cache-local");
+        let cache_dir = dir.path().join("cache");
+        let source = CachedSource::new(
+            Box::new(RecordedSource::new().with("k-nasa", ContributionCalendar::default())),
+            Cache::new(&cache_dir),
+        );
+
+        source.fetch("k-nasa", &DateRange::default()).unwrap();
+
+        assert!(!cache_dir.exists());
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let range = DateRange::new(
+            NaiveDate::from_ymd_opt(2019, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2019, 12, 31).unwrap(),
+        );
+
+        assert_eq!(
+            "html%20https%3A%2F%2Fgithub%2Ecom%20k-nasa%202019-01-01%2E%2E2019-12-31",
+            cache_key("html https://github.com", "k-nasa", &range)
+        );
+        assert_ne!(
+            cache_key("html a", "b_c", &DateRange::default()),
+            cache_key("html a_b", "c", &DateRange::default())
+        );
+    }
+}
@@ -0,0 +1,425 @@
+//! Daily contribution data.
+
+use crate::error::{ContriviewError, Result};
+use chrono::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+
+/// A single day of the contribution calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContributionDay {
+    /// Day of the contributions.
+    pub date: NaiveDate,
+    /// Number of contributions made on `date`.
+    pub count: u32,
+    /// Intensity level as drawn by GitHub, from 0 (no contributions) to 4.
+    pub level: u8,
+}
+
+/// Daily contributions ordered by date.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct ContributionCalendar {
+    days: Vec<ContributionDay>,
+}
+
+impl<'de> Deserialize<'de> for ContributionCalendar {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        Vec::deserialize(deserializer).map(Self::new)
+    }
+}
+
+/// Inclusive range of days. A missing bound leaves that side open.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DateRange {
+    /// First day of the range.
+    pub from: Option<NaiveDate>,
+    /// Last day of the range.
+    pub to: Option<NaiveDate>,
+}
+
+impl DateRange {
+    /// Range from `from` to `to`, both included.
+    pub fn new(from: NaiveDate, to: NaiveDate) -> Self {
+        DateRange {
+            from: Some(from),
+            to: Some(to),
+        }
+    }
+
+    /// The calendar year `year`, or `None` if it is out of chrono's range.
+    pub fn year(year: i32) -> Option<Self> {
+        Some(Self::new(
+            NaiveDate::from_ymd_opt(year, 1, 1)?,
+            NaiveDate::from_ymd_opt(year, 12, 31)?,
+        ))
+    }
+
+    /// Returns true if neither bound is set.
+    pub fn is_unbounded(&self) -> bool {
+        self.from.is_none() && self.to.is_none()
+    }
+
+    /// Fills open bounds with the rolling year ending at `today`, the window the forges show.
+    pub fn or_last_year(&self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let to = self.to.unwrap_or(today);
+        let from = self.from.unwrap_or(to - chrono::Duration::days(365));
+
+        (from, to)
+    }
+
+    /// The range with open bounds filled as by [`or_last_year`](Self::or_last_year), cut to
+    /// the rolling year before `today` that `service` keeps. Fails if an explicit start or the
+    /// end lies before that year, whose days would otherwise look like days without
+    /// contributions.
+    pub fn within_last_year(
+        &self,
+        today: NaiveDate,
+        service: &str,
+    ) -> Result<(NaiveDate, NaiveDate)> {
+        let (from, to) = self.or_last_year(today);
+        let oldest = today - chrono::Duration::days(365);
+
+        if to < oldest || self.from.is_some_and(|from| from < oldest) {
+            return Err(ContriviewError::OutOfWindow {
+                service: service.to_string(),
+                oldest,
+                latest: today,
+            });
+        }
+        Ok((from.max(oldest), to.min(today)))
+    }
+
+    /// Splits the range, with open bounds filled as by [`or_last_year`](Self::or_last_year),
+    /// into windows that never cross a new year. The forges serve at most a year per request.
+    pub fn yearly_windows(&self, today: NaiveDate) -> Vec<(NaiveDate, NaiveDate)> {
+        let (from, to) = self.or_last_year(today);
+
+        let mut windows = vec![];
+        let mut start = from;
+        while start <= to {
+            let end = NaiveDate::from_ymd_opt(start.year(), 12, 31)
+                .unwrap()
+                .min(to);
+            windows.push((start, end));
+            start = match end.succ_opt() {
+                Some(next) => next,
+                None => break,
+            };
+        }
+
+        windows
+    }
+
+    /// Returns true if `date` is within the range.
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.from.is_none_or(|from| from <= date) && self.to.is_none_or(|to| date <= to)
+    }
+}
+
+impl ContributionCalendar {
+    /// Builds a calendar from unordered days. Only the first entry of a duplicated date is kept.
+    pub fn new(mut days: Vec<ContributionDay>) -> Self {
+        days.sort_by_key(|d| d.date);
+        days.dedup_by_key(|d| d.date);
+
+        ContributionCalendar { days }
+    }
+
+    /// Sums several calendars day by day. Levels are recomputed relative to the busiest day.
+    pub fn merge<'a>(calendars: impl IntoIterator<Item = &'a ContributionCalendar>) -> Self {
+        let mut counts: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+        for calendar in calendars {
+            for day in calendar.days() {
+                *counts.entry(day.date).or_insert(0) += day.count;
+            }
+        }
+
+        let max = counts.values().copied().max().unwrap_or_default();
+        let days = counts
+            .into_iter()
+            .map(|(date, count)| ContributionDay {
+                date,
+                count,
+                level: relative_level(count, max),
+            })
+            .collect();
+
+        Self::new(days)
+    }
+
+    /// Joins calendars covering different days, e.g. the windows of a long range. Where they
+    /// overlap, the day of the first calendar is kept.
+    pub fn concat(calendars: impl IntoIterator<Item = ContributionCalendar>) -> Self {
+        Self::new(calendars.into_iter().flat_map(|c| c.days).collect())
+    }
+
+    /// Parses the calendar out of a GitHub contributions page, in any layout supported by
+    /// [`parser`](crate::parser).
+    pub fn from_html(html: &str) -> Result<Self> {
+        crate::parser::parse_html(html)
+    }
+
+    /// All days, oldest first.
+    pub fn days(&self) -> &[ContributionDay] {
+        &self.days
+    }
+
+    /// Returns the days within `range`.
+    pub fn within(&self, range: &DateRange) -> ContributionCalendar {
+        ContributionCalendar {
+            days: self
+                .days
+                .iter()
+                .filter(|d| range.contains(d.date))
+                .copied()
+                .collect(),
+        }
+    }
+
+    /// Adds a zero-contribution day for every date of `from..=to` missing from the calendar.
+    ///
+    /// Sources that only report active days use this so that every day of the window is present.
+    pub fn fill(mut self, from: NaiveDate, to: NaiveDate) -> Self {
+        let missing: Vec<_> = from
+            .iter_days()
+            .take_while(|d| *d <= to)
+            .filter(|d| self.get(*d).is_none())
+            .map(|date| ContributionDay {
+                date,
+                count: 0,
+                level: 0,
+            })
+            .collect();
+
+        self.days.extend(missing);
+        Self::new(self.days)
+    }
+
+    /// Returns true if the calendar has no days.
+    pub fn is_empty(&self) -> bool {
+        self.days.is_empty()
+    }
+
+    /// Returns the entry for `date` if the calendar covers it.
+    pub fn get(&self, date: NaiveDate) -> Option<&ContributionDay> {
+        self.days
+            .binary_search_by_key(&date, |d| d.date)
+            .ok()
+            .map(|i| &self.days[i])
+    }
+}
+
+/// Shades `count` relative to the busiest day, for sources that report no intensity.
+pub(crate) fn relative_level(count: u32, max: u32) -> u8 {
+    if count == 0 || max == 0 {
+        return 0;
+    }
+
+    let level = (u64::from(count) * 4).div_ceil(u64::from(max));
+    level.clamp(1, 4) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::date;
+
+    fn day(date: &str, count: u32, level: u8) -> ContributionDay {
+        ContributionDay {
+            date: NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+            count,
+            level,
+        }
+    }
+
+    #[test]
+    fn test_new_sorts_days() {
+        let calendar =
+            ContributionCalendar::new(vec![day("2019-01-02", 2, 1), day("2019-01-01", 1, 1)]);
+
+        assert_eq!(
+            calendar.days(),
+            &[day("2019-01-01", 1, 1), day("2019-01-02", 2, 1)]
+        );
+    }
+
+    #[test]
+    fn test_from_html() {
+        let html = r##"<svg>
+  <rect class="day" fill="#ebedf0" data-count="0" data-date="2019-01-01"/>
+  <rect class="day" fill="#239a3b" data-count="33" data-date="2019-01-02"/>
+  <rect class="day" fill="#c6e48b" data-count="3" data-date="2019-01-03"/>
+</svg>"##;
+
+        let calendar = ContributionCalendar::from_html(html).unwrap();
+
+        assert_eq!(
+            calendar.days(),
+            &[
+                day("2019-01-01", 0, 0),
+                day("2019-01-02", 33, 3),
+                day("2019-01-03", 3, 1),
+            ]
+        );
+        assert_eq!(
+            calendar.get(NaiveDate::from_ymd_opt(2019, 1, 2).unwrap()),
+            Some(&day("2019-01-02", 33, 3))
+        );
+    }
+
+    #[test]
+    fn test_within() {
+        let calendar = ContributionCalendar::new(vec![
+            day("2019-01-01", 1, 1),
+            day("2019-01-02", 2, 1),
+            day("2019-01-03", 3, 1),
+        ]);
+        let range = DateRange {
+            from: Some(NaiveDate::from_ymd_opt(2019, 1, 2).unwrap()),
+            to: None,
+        };
+
+        assert_eq!(
+            calendar.within(&range).days(),
+            &[day("2019-01-02", 2, 1), day("2019-01-03", 3, 1)]
+        );
+        assert_eq!(calendar.within(&DateRange::default()), calendar);
+    }
+
+    #[test]
+    fn test_fill() {
+        let calendar = ContributionCalendar::new(vec![day("2019-01-02", 2, 1)]).fill(
+            NaiveDate::from_ymd_opt(2019, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2019, 1, 3).unwrap(),
+        );
+
+        assert_eq!(
+            calendar.days(),
+            &[
+                day("2019-01-01", 0, 0),
+                day("2019-01-02", 2, 1),
+                day("2019-01-03", 0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let a = ContributionCalendar::new(vec![day("2019-01-01", 1, 1), day("2019-01-02", 2, 1)]);
+        let b = ContributionCalendar::new(vec![day("2019-01-02", 6, 4), day("2019-01-03", 4, 3)]);
+
+        assert_eq!(
+            ContributionCalendar::merge(vec![&a, &b]).days(),
+            &[
+                day("2019-01-01", 1, 1),
+                day("2019-01-02", 8, 4),
+                day("2019-01-03", 4, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_concat() {
+        let a = ContributionCalendar::new(vec![day("2018-12-31", 1, 1), day("2019-01-01", 2, 1)]);
+        let b = ContributionCalendar::new(vec![day("2019-01-01", 6, 4), day("2019-01-02", 4, 3)]);
+
+        assert_eq!(
+            ContributionCalendar::concat(vec![a, b]).days(),
+            &[
+                day("2018-12-31", 1, 1),
+                day("2019-01-01", 2, 1),
+                day("2019-01-02", 4, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_within_last_year() {
+        let today = date("2019-01-26");
+
+        assert_eq!(
+            (date("2018-01-26"), today),
+            DateRange::default()
+                .within_last_year(today, "GitLab")
+                .unwrap()
+        );
+        assert_eq!(
+            (date("2019-01-01"), today),
+            DateRange::new(date("2019-01-01"), date("2019-12-31"))
+                .within_last_year(today, "GitLab")
+                .unwrap()
+        );
+        assert_eq!(
+            (date("2018-01-26"), date("2019-01-10")),
+            DateRange {
+                from: None,
+                to: Some(date("2019-01-10")),
+            }
+            .within_last_year(today, "GitLab")
+            .unwrap()
+        );
+        assert!(DateRange {
+            from: None,
+            to: Some(date("2017-12-31")),
+        }
+        .within_last_year(today, "GitLab")
+        .is_err());
+        assert_eq!(
+            "GitLab only serves contributions from 2018-01-26 to 2019-01-26",
+            DateRange::year(2017)
+                .unwrap()
+                .within_last_year(today, "GitLab")
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_yearly_windows() {
+        let range = DateRange::new(date("2017-05-01"), date("2019-01-10"));
+
+        assert_eq!(
+            range.yearly_windows(date("2020-01-01")),
+            vec![
+                (date("2017-05-01"), date("2017-12-31")),
+                (date("2018-01-01"), date("2018-12-31")),
+                (date("2019-01-01"), date("2019-01-10")),
+            ]
+        );
+        assert_eq!(
+            DateRange::default().yearly_windows(date("2019-01-26")),
+            vec![
+                (date("2018-01-26"), date("2018-12-31")),
+                (date("2019-01-01"), date("2019-01-26")),
+            ]
+        );
+        assert_eq!(
+            DateRange::year(2016)
+                .unwrap()
+                .yearly_windows(date("2019-01-26")),
+            vec![(date("2016-01-01"), date("2016-12-31"))]
+        );
+    }
+
+    #[test]
+    fn test_relative_level() {
+        assert_eq!(0, relative_level(0, 10));
+        assert_eq!(1, relative_level(1, 10));
+        assert_eq!(2, relative_level(5, 10));
+        assert_eq!(4, relative_level(10, 10));
+    }
+
+    #[test]
+    fn test_deserialize_sorts_days() {
+        let calendar: ContributionCalendar = serde_json::from_str(
+            r#"[{"date": "2019-01-02", "count": 2, "level": 1}, {"date": "2019-01-01", "count": 1, "level": 1}]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            calendar.days(),
+            &[day("2019-01-01", 1, 1), day("2019-01-02", 2, 1)]
+        );
+    }
+}
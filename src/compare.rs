@@ -0,0 +1,263 @@
+//! Side-by-side summaries of several users.
+
+use crate::contriview::ContriView;
+use crate::error::{ContriviewError, Result};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Column name that sorts by username.
+pub const USER_COLUMN: &str = "user";
+
+/// Fields the totals row sums. Sums of the other fields, like a summed median or streak, mean
+/// nothing.
+const SUMMED_FIELDS: &[&str] = &[
+    "today_contributions",
+    "week_contributions",
+    "month_contributions",
+    "year_contributions",
+    "sum_contributions",
+    "week_ave",
+    "month_ave",
+    "sum_ave",
+];
+
+/// One summary per user, printed as a table with a totals row.
+#[derive(Debug, Default)]
+pub struct Comparison {
+    rows: Vec<(String, ContriView)>,
+}
+
+impl Comparison {
+    /// Compares `rows` in the given order.
+    pub fn new(rows: Vec<(String, ContriView)>) -> Self {
+        Comparison { rows }
+    }
+
+    /// Users and their summaries, in display order.
+    pub fn rows(&self) -> &[(String, ContriView)] {
+        &self.rows
+    }
+
+    /// Sorts alphabetically by [`USER_COLUMN`], or by one of [`ContriView::FIELDS`] with the
+    /// largest value first. `reverse` flips the order; ties keep their current order.
+    pub fn sort_by(&mut self, column: &str, reverse: bool) -> Result<()> {
+        if column != USER_COLUMN && ContriView::default().field(column).is_none() {
+            return Err(ContriviewError::UnknownField(column.to_string()));
+        }
+
+        self.rows.sort_by(|(a_user, a), (b_user, b)| {
+            let ordering = match (a.field(column), b.field(column)) {
+                (Some(a), Some(b)) => b.partial_cmp(&a).unwrap_or(Ordering::Equal),
+                _ => a_user.cmp(b_user),
+            };
+
+            if reverse {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Sum of each field over all users, in [`ContriView::FIELDS`] order, or `None` for the
+    /// fields whose sum means nothing, like the median or the streaks.
+    pub fn totals(&self) -> Vec<Option<f64>> {
+        ContriView::FIELDS
+            .iter()
+            .map(|f| {
+                SUMMED_FIELDS
+                    .contains(f)
+                    .then(|| self.rows.iter().filter_map(|(_, v)| v.field(f)).sum())
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut table: Vec<Vec<String>> = vec![];
+
+        let mut header = vec![USER_COLUMN.to_string()];
+        header.extend(
+            ContriView::FIELDS
+                .iter()
+                .map(|f| f.trim_end_matches("_contributions").to_string()),
+        );
+        table.push(header);
+
+        for (user, view) in &self.rows {
+            let mut row = vec![user.clone()];
+            row.extend(
+                ContriView::FIELDS
+                    .iter()
+                    .map(|f| format_value(view.field(f).unwrap_or_default())),
+            );
+            table.push(row);
+        }
+
+        let mut totals = vec!["total".to_string()];
+        totals.extend(
+            self.totals()
+                .into_iter()
+                .map(|total| total.map(format_value).unwrap_or_default()),
+        );
+        table.push(totals);
+
+        write_table(f, &table, 1, true)
+    }
+}
+
+/// Writes `table` with aligned columns: the first `labels` left-aligned, the others
+/// right-aligned. With `totals`, the last row is set apart by a dashed rule.
+pub(crate) fn write_table(
+    f: &mut fmt::Formatter,
+    table: &[Vec<String>],
+    labels: usize,
+    totals: bool,
+) -> fmt::Result {
+    let widths: Vec<usize> = (0..table.first().map_or(0, Vec::len))
+        .map(|i| {
+            table
+                .iter()
+                .map(|r| r[i].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    for (i, row) in table.iter().enumerate() {
+        if totals && i == table.len() - 1 {
+            let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+            writeln!(f, "{}", rule.join("  "))?;
+        }
+
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(j, (cell, width))| {
+                if j < labels {
+                    format!("{:<width$}", cell, width = width)
+                } else {
+                    format!("{:>width$}", cell, width = width)
+                }
+            })
+            .collect();
+        write!(f, "{}", cells.join("  ").trim_end())?;
+
+        if i != table.len() - 1 {
+            writeln!(f)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::{ContributionCalendar, ContributionDay};
+    use chrono::NaiveDate;
+
+    fn view(today: u32, past: u32) -> ContriView {
+        let date = NaiveDate::from_ymd_opt(2019, 1, 26).unwrap();
+        let calendar = ContributionCalendar::new(vec![
+            ContributionDay {
+                date: date.pred_opt().unwrap(),
+                count: past,
+                level: 1,
+            },
+            ContributionDay {
+                date,
+                count: today,
+                level: 1,
+            },
+        ]);
+
+        ContriView::from_calendar(&calendar, date)
+    }
+
+    fn comparison() -> Comparison {
+        Comparison::new(vec![
+            ("carol".to_string(), view(1, 30)),
+            ("alice".to_string(), view(7, 0)),
+            ("bob".to_string(), view(3, 4)),
+        ])
+    }
+
+    fn users(comparison: &Comparison) -> Vec<&str> {
+        comparison.rows().iter().map(|(u, _)| u.as_str()).collect()
+    }
+
+    #[test]
+    fn test_sort_by() {
+        let mut comparison = comparison();
+
+        comparison.sort_by("today", false).unwrap();
+        assert_eq!(vec!["alice", "bob", "carol"], users(&comparison));
+
+        comparison.sort_by("sum_contributions", true).unwrap();
+        assert_eq!(vec!["alice", "bob", "carol"], users(&comparison));
+
+        comparison.sort_by("sum", false).unwrap();
+        assert_eq!(vec!["carol", "alice", "bob"], users(&comparison));
+
+        comparison.sort_by(USER_COLUMN, false).unwrap();
+        assert_eq!(vec!["alice", "bob", "carol"], users(&comparison));
+
+        assert!(matches!(
+            comparison.sort_by("streak", false),
+            Err(ContriviewError::UnknownField(_))
+        ));
+    }
+
+    #[test]
+    fn test_totals() {
+        assert_eq!(
+            vec![
+                Some(11.0),
+                Some(45.0),
+                Some(45.0),
+                Some(45.0),
+                Some(45.0),
+                Some(22.5),
+                Some(22.5),
+                Some(22.5),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None
+            ],
+            comparison().totals()
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            comparison().to_string(),
+            "user   today  week  month  year  sum  week_ave  month_ave  sum_ave  current_streak  longest_streak  current_gap  longest_gap  median  std_dev    p75    p90    p99  active_day_ave
+carol      1    31     31    31   31     15.50      15.50    15.50               2               2            0            0   15.50    14.50  22.75  27.10  29.71           15.50
+alice      7     7      7     7    7      3.50       3.50     3.50               1               1            0            1    3.50     3.50   5.25   6.30   6.93               7
+bob        3     7      7     7    7      3.50       3.50     3.50               2               2            0            0    3.50     0.50   3.75   3.90   3.99            3.50
+-----  -----  ----  -----  ----  ---  --------  ---------  -------  --------------  --------------  -----------  -----------  ------  -------  -----  -----  -----  --------------
+total     11    45     45    45   45     22.50      22.50    22.50"
+        );
+    }
+}
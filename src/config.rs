@@ -0,0 +1,311 @@
+//! The configuration file, `~/.config/contriview/config.toml`.
+
+use crate::error::{ContriviewError, Result};
+use crate::export::Theme;
+use crate::format::OutputFormat;
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Names accepted by `--source` and the `source` setting.
+pub const SOURCES: &[&str] = &["html", "graphql", "gitlab", "gitea", "file", "recorded"];
+
+/// Names accepted by `--week-mode` and the `week_mode` setting.
+pub const WEEK_MODES: &[&str] = &["rolling", "iso", "calendar"];
+
+/// Settings read from the configuration file. Command-line flags take precedence over them.
+///
+/// ```toml
+/// user = "k-nasa"
+/// source = "graphql"
+/// token = "ghp_..."
+/// format = "json"
+/// week_mode = "calendar"
+/// week_start = "sunday"
+/// timezone = "Asia/Tokyo"
+/// theme = "dark"
+///
+/// [teams]
+/// backend = ["alice", "bob"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Account summarized when no username is given.
+    pub user: Option<String>,
+    /// GitHub token for the graphql source.
+    pub token: Option<String>,
+    /// Default `--source`.
+    pub source: Option<String>,
+    /// Default `--host`.
+    pub host: Option<String>,
+    /// Default `--format`.
+    pub format: Option<String>,
+    /// Default `--week-mode`. Defaults to `calendar` if `week_start` is set, `rolling` otherwise.
+    pub week_mode: Option<String>,
+    /// Default `--week-start`.
+    pub week_start: Option<String>,
+    /// IANA name of the timezone "today" is taken in, e.g. `Asia/Tokyo`. Defaults to the
+    /// system timezone.
+    pub timezone: Option<String>,
+    /// Default `--theme` of `contriview export`.
+    pub theme: Option<String>,
+    /// Named lists of accounts for `--team`.
+    pub teams: BTreeMap<String, Vec<String>>,
+}
+
+impl Config {
+    /// Location of the configuration file, if the platform has a configuration directory.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|d| d.join("contriview").join("config.toml"))
+    }
+
+    /// Loads the configuration at [`Config::default_path`], or the defaults if there is none.
+    pub fn load() -> Result<Self> {
+        match Self::default_path() {
+            Some(path) if path.is_file() => Self::from_file(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Loads the configuration at `path`.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path).map_err(|source| ContriviewError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let config: Config = toml::from_str(&text)
+            .map_err(|e| ContriviewError::Config(format!("{}: {}", path.display(), e)))?;
+        config
+            .validate()
+            .map_err(|e| ContriviewError::Config(format!("{}: {}", path.display(), e)))?;
+
+        Ok(config)
+    }
+
+    /// Checks the values that are only known as strings, returning the first invalid one.
+    fn validate(&self) -> std::result::Result<(), String> {
+        let invalid = |key: &str, value: &str| Err(format!("invalid {} {:?}", key, value));
+
+        if let Some(format) = &self.format {
+            if format.parse::<OutputFormat>().is_err() {
+                return invalid("format", format);
+            }
+        }
+        if let Some(source) = &self.source {
+            if !SOURCES.contains(&source.as_str()) {
+                return invalid("source", source);
+            }
+        }
+        if let Some(mode) = &self.week_mode {
+            if !WEEK_MODES.contains(&mode.as_str()) {
+                return invalid("week_mode", mode);
+            }
+        }
+        if let Some(day) = &self.week_start {
+            if day.parse::<Weekday>().is_err() {
+                return invalid("week_start", day);
+            }
+        }
+        if let Some(timezone) = &self.timezone {
+            if timezone.parse::<chrono_tz::Tz>().is_err() {
+                return invalid("timezone", timezone);
+            }
+        }
+        if let Some(theme) = &self.theme {
+            if Theme::named(theme).is_none() {
+                return invalid("theme", theme);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Members of the team called `name`.
+    pub fn team(&self, name: &str) -> Result<&[String]> {
+        self.teams
+            .get(name)
+            .map(Vec::as_slice)
+            .ok_or_else(|| ContriviewError::UnknownTeam(name.to_string()))
+    }
+
+    /// The default user followed by the members of every team, each once.
+    pub fn users(&self) -> Vec<String> {
+        let mut users: Vec<String> = self.user.iter().cloned().collect();
+        for member in self.teams.values().flatten() {
+            if !users.contains(member) {
+                users.push(member.clone());
+            }
+        }
+        users
+    }
+
+    /// The configured timezone, `None` for the system one.
+    pub fn tz(&self) -> Option<chrono_tz::Tz> {
+        self.timezone.as_deref().and_then(|tz| tz.parse().ok())
+    }
+
+    /// Today in the configured timezone.
+    pub fn today(&self) -> NaiveDate {
+        match self.tz() {
+            Some(tz) => Utc::now().with_timezone(&tz).date_naive(),
+            None => Local::now().date_naive(),
+        }
+    }
+
+    /// These settings with the ones set in `flags` taking precedence, one setting at a time. A
+    /// first day of the week given without a week mode selects calendar weeks, whatever mode
+    /// these settings name.
+    pub fn overridden_by(self, flags: Config) -> Self {
+        let week_mode = match (&flags.week_mode, &flags.week_start) {
+            (None, Some(_)) => Some("calendar".to_string()),
+            _ => flags.week_mode.or(self.week_mode),
+        };
+
+        Config {
+            user: flags.user.or(self.user),
+            token: flags.token.or(self.token),
+            source: flags.source.or(self.source),
+            host: flags.host.or(self.host),
+            format: flags.format.or(self.format),
+            week_mode,
+            week_start: flags.week_start.or(self.week_start),
+            timezone: flags.timezone.or(self.timezone),
+            theme: flags.theme.or(self.theme),
+            teams: self.teams.into_iter().chain(flags.teams).collect(),
+        }
+    }
+
+    /// A copy safe to print, with the token hidden.
+    pub fn redacted(&self) -> Self {
+        Config {
+            token: self.token.as_ref().map(|_| "<redacted>".to_string()),
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::TempDir;
+
+    #[test]
+    fn test_team() {
+        let config: Config = toml::from_str(
+            r#"
+[teams]
+backend = ["alice", "bob"]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            &["alice".to_string(), "bob".to_string()],
+            config.team("backend").unwrap()
+        );
+        assert!(matches!(
+            config.team("frontend"),
+            Err(ContriviewError::UnknownTeam(_))
+        ));
+    }
+
+    #[test]
+    fn test_settings() {
+        let config: Config = toml::from_str(
+            r#"
+user = "k-nasa"
+token = "secret"
+source = "graphql"
+format = "json"
+week_start = "sunday"
+timezone = "Asia/Tokyo"
+theme = "dark"
+
+[teams]
+backend = ["alice", "k-nasa"]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(Ok(()), config.validate());
+        assert_eq!(Some("k-nasa"), config.user.as_deref());
+        assert_eq!(vec!["k-nasa", "alice"], config.users());
+        assert_eq!(Some("<redacted>"), config.redacted().token.as_deref());
+        assert_eq!(Some("secret"), config.token.as_deref());
+        assert_eq!(Some(chrono_tz::Asia::Tokyo), config.tz());
+    }
+
+    #[test]
+    fn test_overridden_by() {
+        let config = Config {
+            source: Some("gitlab".to_string()),
+            format: Some("json".to_string()),
+            week_mode: Some("rolling".to_string()),
+            week_start: Some("sunday".to_string()),
+            ..Config::default()
+        };
+        let flag = |value: &str| Some(value.to_string());
+
+        let settings = config.clone().overridden_by(Config {
+            format: flag("toml"),
+            ..Config::default()
+        });
+        assert_eq!(flag("gitlab"), settings.source);
+        assert_eq!(flag("toml"), settings.format);
+        assert_eq!(flag("rolling"), settings.week_mode);
+
+        let settings = config.clone().overridden_by(Config {
+            week_start: flag("monday"),
+            ..Config::default()
+        });
+        assert_eq!(flag("calendar"), settings.week_mode);
+        assert_eq!(flag("monday"), settings.week_start);
+
+        let settings = config.overridden_by(Config {
+            week_mode: flag("iso"),
+            week_start: flag("monday"),
+            ..Config::default()
+        });
+        assert_eq!(flag("iso"), settings.week_mode);
+    }
+
+    #[test]
+    fn test_invalid_settings() {
+        for text in &[
+            "format = \"xml\"",
+            "source = \"svn\"",
+            "week_mode = \"fortnightly\"",
+            "week_start = \"someday\"",
+            "timezone = \"Mars/Olympus\"",
+            "theme = \"neon\"",
+        ] {
+            let config: Config = toml::from_str(text).unwrap();
+            assert!(config.validate().is_err(), "{}", text);
+        }
+        assert!(toml::from_str::<Config>("usr = \"typo\"").is_err());
+    }
+
+    #[test]
+    fn test_from_file() {
+        let dir = TempDir::new("config");
+        let path = dir.path().join("config.toml");
+
+        std::fs::write(&path, "").unwrap();
+        assert_eq!(Config::default(), Config::from_file(&path).unwrap());
+
+        std::fs::write(&path, "teams = 1").unwrap();
+        assert!(matches!(
+            Config::from_file(&path),
+            Err(ContriviewError::Config(_))
+        ));
+
+        std::fs::write(&path, "timezone = \"Mars/Olympus\"").unwrap();
+        assert!(matches!(
+            Config::from_file(&path),
+            Err(ContriviewError::Config(e)) if e.ends_with("invalid timezone \"Mars/Olympus\"")
+        ));
+    }
+}
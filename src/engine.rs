@@ -0,0 +1,151 @@
+//! Fetching the calendars of many users at once.
+
+use crate::calendar::{ContributionCalendar, DateRange};
+use crate::error::Result;
+use crate::source::ContributionSource;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// Runs fetches on a fixed number of threads.
+///
+/// Rate limits and retries are the business of the source's
+/// [`HttpClient`](crate::http::HttpClient); the engine only bounds how many fetches are in
+/// flight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FetchEngine {
+    concurrency: usize,
+}
+
+impl Default for FetchEngine {
+    fn default() -> Self {
+        FetchEngine { concurrency: 4 }
+    }
+}
+
+impl FetchEngine {
+    /// An engine running up to `concurrency` fetches at a time, at least one.
+    pub fn new(concurrency: usize) -> Self {
+        FetchEngine {
+            concurrency: concurrency.max(1),
+        }
+    }
+
+    /// Fetches every user in `usernames`. Results are in the order of `usernames`.
+    pub fn fetch_all(
+        &self,
+        source: &dyn ContributionSource,
+        usernames: &[String],
+        range: &DateRange,
+    ) -> Vec<Result<ContributionCalendar>> {
+        let next = AtomicUsize::new(0);
+        let results: Mutex<Vec<Option<Result<ContributionCalendar>>>> =
+            Mutex::new(usernames.iter().map(|_| None).collect());
+
+        thread::scope(|scope| {
+            for _ in 0..self.concurrency.min(usernames.len()) {
+                scope.spawn(|| loop {
+                    let i = next.fetch_add(1, Ordering::SeqCst);
+                    let username = match usernames.get(i) {
+                        Some(u) => u,
+                        None => break,
+                    };
+
+                    let result = source.fetch(username, range);
+                    results.lock().unwrap()[i] = Some(result);
+                });
+            }
+        });
+
+        results
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .map(|r| r.expect("every user is fetched"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ContriviewError;
+    use crate::fetch::GithubHtmlSource;
+    use crate::fixtures::sample_html;
+    use crate::http::{HttpClient, RetryPolicy};
+    use crate::mock_server::{MockServer, Response};
+    use std::time::{Duration, Instant};
+
+    fn users(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn test_fetch_all_keeps_order() {
+        let server = MockServer::start(|request| {
+            if request.path.contains("nobody") {
+                Response::new(404, "")
+            } else {
+                Response::new(200, sample_html())
+            }
+        });
+        let source = GithubHtmlSource::new().with_host(server.url());
+
+        let results = FetchEngine::new(2).fetch_all(
+            &source,
+            &users(&["alice", "nobody", "bob"]),
+            &DateRange::default(),
+        );
+
+        assert_eq!(3, results.len());
+        assert_eq!(371, results[0].as_ref().unwrap().days().len());
+        assert!(matches!(results[1], Err(ContriviewError::UserNotFound(_))));
+        assert!(results[2].is_ok());
+    }
+
+    #[test]
+    fn test_fetch_all_runs_concurrently() {
+        let server = MockServer::start(|_| {
+            std::thread::sleep(Duration::from_millis(200));
+            Response::new(200, sample_html())
+        });
+        let source = GithubHtmlSource::new().with_host(server.url());
+        let names = users(&["a", "b", "c", "d"]);
+
+        let started = Instant::now();
+        let results = FetchEngine::new(4).fetch_all(&source, &names, &DateRange::default());
+        let concurrent = started.elapsed();
+
+        let started = Instant::now();
+        FetchEngine::new(1).fetch_all(&source, &names, &DateRange::default());
+        let serial = started.elapsed();
+
+        assert!(results.iter().all(|r| r.is_ok()));
+        assert_eq!(8, server.requests().len());
+        assert!(concurrent < Duration::from_millis(600));
+        assert!(serial >= Duration::from_millis(800));
+    }
+
+    #[test]
+    fn test_fetch_all_retries_rate_limited_users() {
+        let server = MockServer::sequence(vec![
+            Response::new(429, "").header("Retry-After", "0"),
+            Response::new(502, ""),
+            Response::new(200, sample_html()),
+        ]);
+        let http = HttpClient::new().with_retry(RetryPolicy {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+        });
+        let source = GithubHtmlSource::new()
+            .with_host(server.url())
+            .with_http(http);
+
+        let results =
+            FetchEngine::new(1).fetch_all(&source, &users(&["alice"]), &DateRange::default());
+
+        assert!(results[0].is_ok());
+        assert_eq!(3, server.requests().len());
+    }
+}
@@ -0,0 +1,251 @@
+//! Errors returned by contriview.
+
+use chrono::NaiveDate;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Result alias used throughout the crate.
+pub type Result<T> = std::result::Result<T, ContriviewError>;
+
+/// Everything that can go wrong while fetching and summarizing contributions.
+#[derive(Debug, Error)]
+pub enum ContriviewError {
+    /// The request could not be sent or its body could not be read.
+    #[error("failed to fetch {url}: {source}")]
+    Network {
+        /// Requested URL.
+        url: String,
+        /// Underlying transport error.
+        source: reqwest::Error,
+    },
+
+    /// The server answered with an unexpected status code.
+    #[error("{url} returned HTTP {status}")]
+    HttpStatus {
+        /// Requested URL.
+        url: String,
+        /// Status code of the response.
+        status: u16,
+    },
+
+    /// A local file could not be read.
+    #[error("failed to read {}: {source}", path.display())]
+    Io {
+        /// Path of the file.
+        path: PathBuf,
+        /// Underlying I/O error.
+        source: std::io::Error,
+    },
+
+    /// A git command failed on a repository.
+    #[error("git failed in {}: {message}", repo.display())]
+    Git {
+        /// Path of the repository.
+        repo: PathBuf,
+        /// Output of git.
+        message: String,
+    },
+
+    /// The requested account does not exist.
+    #[error("user {0} was not found")]
+    UserNotFound(String),
+
+    /// The page does not look like a contribution calendar anymore.
+    #[error("unexpected contribution calendar markup: {0}")]
+    MarkupChanged(String),
+
+    /// The API answered the query with an error.
+    #[error("API error: {0}")]
+    Api(String),
+
+    /// The source only keeps the last year and the range starts earlier.
+    #[error("{service} only serves contributions from {oldest} to {latest}")]
+    OutOfWindow {
+        /// Name of the source.
+        service: String,
+        /// First day the source serves.
+        oldest: NaiveDate,
+        /// Last day the source serves, today.
+        latest: NaiveDate,
+    },
+
+    /// A date could not be parsed.
+    #[error("invalid date {0:?}, expected YYYY-MM-DD")]
+    InvalidDate(String),
+
+    /// The ISO year of the date has no such week.
+    #[error("invalid week {0}, ISO years have 52 or 53 weeks")]
+    InvalidWeek(u32),
+
+    /// A `--period` was not recognized or ends before it starts.
+    #[error("invalid period {0:?}, expected Nd, mtd, qtd, ytd, fy or YYYY-MM-DD..YYYY-MM-DD")]
+    InvalidPeriod(String),
+
+    /// A color scale is not five `#rrggbb` colors.
+    #[error("invalid color scale {0:?}, expected five comma-separated #rrggbb colors")]
+    InvalidColor(String),
+
+    /// An output format name was not recognized.
+    #[error("unknown output format: {0}")]
+    UnknownFormat(String),
+
+    /// A summary field name was not recognized.
+    #[error("unknown field: {0}")]
+    UnknownField(String),
+
+    /// No team of that name is configured.
+    #[error("team {0} is not defined in the configuration")]
+    UnknownTeam(String),
+
+    /// The configuration file is invalid.
+    #[error("invalid configuration: {0}")]
+    Config(String),
+
+    /// `--offline` was given but nothing is cached for the request.
+    #[error("no cached calendar for {0}, run once without --offline")]
+    NotCached(String),
+
+    /// The history database could not be read or written.
+    #[error("history database: {0}")]
+    History(String),
+
+    /// The terminal could not be set up or read for `contriview tui`.
+    #[error("terminal: {0}")]
+    Terminal(std::io::Error),
+
+    /// The report could not be serialized.
+    #[error("failed to render output: {0}")]
+    Output(String),
+}
+
+/// Exit code of usage errors, from contriview and from clap alike.
+pub const USAGE_EXIT_CODE: i32 = 2;
+
+impl ContriviewError {
+    /// Process exit code for this error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ContriviewError::InvalidDate(_)
+            | ContriviewError::InvalidWeek(_)
+            | ContriviewError::InvalidPeriod(_)
+            | ContriviewError::InvalidColor(_)
+            | ContriviewError::UnknownFormat(_)
+            | ContriviewError::UnknownField(_)
+            | ContriviewError::UnknownTeam(_)
+            | ContriviewError::OutOfWindow { .. } => USAGE_EXIT_CODE,
+            ContriviewError::Network { .. } => 3,
+            ContriviewError::HttpStatus { .. } => 4,
+            ContriviewError::UserNotFound(_) => 5,
+            ContriviewError::MarkupChanged(_) => 6,
+            ContriviewError::Output(_) => 7,
+            ContriviewError::Api(_) => 8,
+            ContriviewError::Io { .. } => 9,
+            ContriviewError::Git { .. } => 10,
+            ContriviewError::Config(_) => 11,
+            ContriviewError::NotCached(_) => 12,
+            ContriviewError::History(_) => 13,
+            ContriviewError::Terminal(_) => 14,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One error of every variant with the exit code it should have.
+    fn every_variant() -> Vec<(ContriviewError, i32)> {
+        let network = reqwest::blocking::Client::new()
+            .get("not a url")
+            .build()
+            .unwrap_err();
+        let date = NaiveDate::from_ymd_opt(2019, 1, 26).unwrap();
+
+        vec![
+            (ContriviewError::InvalidDate("2019-13-01".into()), 2),
+            (ContriviewError::InvalidWeek(54), 2),
+            (ContriviewError::InvalidPeriod("7w".into()), 2),
+            (ContriviewError::InvalidColor("#fff".into()), 2),
+            (ContriviewError::UnknownFormat("xml".into()), 2),
+            (ContriviewError::UnknownField("total".into()), 2),
+            (ContriviewError::UnknownTeam("backend".into()), 2),
+            (
+                ContriviewError::OutOfWindow {
+                    service: "GitLab".into(),
+                    oldest: date,
+                    latest: date,
+                },
+                2,
+            ),
+            (
+                ContriviewError::Network {
+                    url: "not a url".into(),
+                    source: network,
+                },
+                3,
+            ),
+            (
+                ContriviewError::HttpStatus {
+                    url: String::new(),
+                    status: 500,
+                },
+                4,
+            ),
+            (ContriviewError::UserNotFound("k-nasa".into()), 5),
+            (ContriviewError::MarkupChanged("no days".into()), 6),
+            (ContriviewError::Output("broken".into()), 7),
+            (ContriviewError::Api("rate limited".into()), 8),
+            (
+                ContriviewError::Io {
+                    path: PathBuf::from("contributions.html"),
+                    source: std::io::ErrorKind::NotFound.into(),
+                },
+                9,
+            ),
+            (
+                ContriviewError::Git {
+                    repo: PathBuf::from("."),
+                    message: "not a git repository".into(),
+                },
+                10,
+            ),
+            (ContriviewError::Config("teams = 1".into()), 11),
+            (ContriviewError::NotCached("k-nasa".into()), 12),
+            (ContriviewError::History("database is locked".into()), 13),
+            (
+                ContriviewError::Terminal(std::io::ErrorKind::Unsupported.into()),
+                14,
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        let errors = every_variant();
+        let variants: std::collections::HashSet<_> = errors
+            .iter()
+            .map(|(e, _)| std::mem::discriminant(e))
+            .collect();
+        // Counted by hand: update it along with `every_variant` when adding a variant.
+        assert_eq!(20, variants.len());
+
+        for (error, code) in &errors {
+            assert_eq!(*code, error.exit_code(), "{:?}", error);
+        }
+    }
+
+    #[test]
+    fn test_exit_codes_are_distinct() {
+        let mut codes: Vec<i32> = every_variant()
+            .iter()
+            .map(|(e, _)| e.exit_code())
+            .filter(|&code| code != USAGE_EXIT_CODE)
+            .collect();
+        let count = codes.len();
+        codes.sort_unstable();
+        codes.dedup();
+
+        assert_eq!(count, codes.len());
+        assert!(!codes.contains(&0));
+    }
+}
@@ -0,0 +1,411 @@
+//! The contribution calendar as an SVG or PNG image, for READMEs and wiki pages.
+
+use crate::calendar::ContributionCalendar;
+use crate::contriview::ContriView;
+use crate::error::{ContriviewError, Result};
+use crate::parser::PALETTE;
+use chrono::prelude::*;
+use std::fmt::Write;
+
+const CELL: u32 = 10;
+const STEP: u32 = 13;
+const PADDING: u32 = 16;
+const GUTTER: u32 = 30;
+const LINE: u32 = 20;
+
+/// Average advance of a sans-serif character, in ems. Generous, so that text fits whatever
+/// font renders it.
+const CHAR_WIDTH: f64 = 0.6;
+
+/// Colors of an exported image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    /// Background of the image.
+    pub background: String,
+    /// Title, summary and labels.
+    pub text: String,
+    /// Fill of the days, indexed by intensity level.
+    pub scale: [String; 5],
+}
+
+impl Theme {
+    /// Names accepted by [`named`](Self::named).
+    pub const NAMES: &'static [&'static str] = &["light", "dark", "halloween"];
+
+    /// A built-in theme.
+    pub fn named(name: &str) -> Option<Self> {
+        let (background, text, scale) = match name {
+            "light" => ("#ffffff", "#24292f", PALETTE),
+            "dark" => (
+                "#0d1117",
+                "#c9d1d9",
+                ["#161b22", "#0e4429", "#006d32", "#26a641", "#39d353"],
+            ),
+            "halloween" => (
+                "#ffffff",
+                "#24292f",
+                ["#ebedf0", "#ffee4a", "#ffc501", "#fe9600", "#03001c"],
+            ),
+            _ => return None,
+        };
+
+        Some(Theme {
+            background: background.to_string(),
+            text: text.to_string(),
+            scale: scale.map(String::from),
+        })
+    }
+
+    /// Replaces the scale with five comma-separated `#rrggbb` colors, from no contributions to
+    /// the busiest days.
+    pub fn with_scale(mut self, colors: &str) -> Result<Self> {
+        let invalid = || ContriviewError::InvalidColor(colors.to_string());
+        let colors: Vec<&str> = colors.split(',').map(str::trim).collect();
+        if colors.len() != 5 || !colors.iter().all(|c| is_hex_color(c)) {
+            return Err(invalid());
+        }
+
+        for (slot, color) in self.scale.iter_mut().zip(colors) {
+            *slot = color.to_string();
+        }
+        Ok(self)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::named("light").unwrap()
+    }
+}
+
+fn is_hex_color(s: &str) -> bool {
+    s.len() == 7 && s.starts_with('#') && s[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Estimated width in pixels of `text` drawn at `size`.
+fn text_width(text: &str, size: u32) -> u32 {
+    (text.chars().count() as f64 * f64::from(size) * CHAR_WIDTH).ceil() as u32
+}
+
+fn days(n: u32) -> String {
+    match n {
+        1 => "1 day".to_string(),
+        n => format!("{} days", n),
+    }
+}
+
+/// Escapes text for SVG content and attributes.
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// An image of the days of a calendar between two dates, one column per week from Sunday to
+/// Saturday.
+#[derive(Debug, Clone)]
+pub struct Export<'a> {
+    calendar: &'a ContributionCalendar,
+    from: NaiveDate,
+    to: NaiveDate,
+    theme: Theme,
+    title: Option<String>,
+    summary: Option<&'a ContriView>,
+}
+
+impl<'a> Export<'a> {
+    /// Draws the days of `calendar` from `from` to `to`, both included.
+    pub fn new(calendar: &'a ContributionCalendar, from: NaiveDate, to: NaiveDate) -> Self {
+        Export {
+            calendar,
+            from,
+            to,
+            theme: Theme::default(),
+            title: None,
+            summary: None,
+        }
+    }
+
+    /// Draws in the colors of `theme`.
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Writes `title` above the calendar.
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    /// Writes the totals of `summary` above the calendar.
+    pub fn with_summary(mut self, summary: &'a ContriView) -> Self {
+        self.summary = Some(summary);
+        self
+    }
+
+    /// Sunday of the first column.
+    fn start(&self) -> NaiveDate {
+        self.from - chrono::Duration::days(i64::from(self.from.weekday().num_days_from_sunday()))
+    }
+
+    fn weeks(&self) -> u32 {
+        ((self.to - self.start()).num_days() / 7 + 1).max(1) as u32
+    }
+
+    fn header_lines(&self) -> Vec<(String, u32)> {
+        let mut lines = vec![];
+        if let Some(title) = &self.title {
+            lines.push((title.clone(), 16));
+        }
+        if let Some(summary) = self.summary {
+            let text = format!(
+                "{} contributions, {:.2} a day, longest streak {}, current streak {}",
+                summary.sum_contributions(),
+                summary.sum_ave(),
+                days(summary.longest_streak()),
+                days(summary.current_streak())
+            );
+            lines.push((text, 12));
+        }
+        lines
+    }
+
+    /// Width and height of the image in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        let lines = self.header_lines();
+        let text = lines
+            .iter()
+            .map(|(text, size)| text_width(text, *size))
+            .max()
+            .unwrap_or(0);
+        // Short ranges are widened so that the legend and the header still fit.
+        let width = (PADDING * 2 + GUTTER + self.weeks() * STEP)
+            .max(PADDING * 2 + 5 * STEP + 64)
+            .max(PADDING * 2 + text);
+        let header = lines.len() as u32 * LINE;
+        let height = PADDING * 2 + header + LINE + 7 * STEP + LINE;
+
+        (width, height)
+    }
+
+    /// The image as an SVG document.
+    pub fn to_svg(&self) -> String {
+        let (width, height) = self.size();
+        let font = "font-family=\"-apple-system, Helvetica, Arial, sans-serif\"";
+        let mut svg = String::new();
+
+        let _ = writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+            w = width,
+            h = height
+        );
+        let _ = writeln!(
+            svg,
+            "<rect width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+            width, height, self.theme.background
+        );
+
+        let mut y = PADDING;
+        for (text, size) in self.header_lines() {
+            y += LINE;
+            let _ = writeln!(
+                svg,
+                "<text x=\"{}\" y=\"{}\" font-size=\"{}\" {} fill=\"{}\">{}</text>",
+                PADDING,
+                y - 6,
+                size,
+                font,
+                self.theme.text,
+                escape(&text)
+            );
+        }
+
+        let left = PADDING + GUTTER;
+        let top = y + LINE;
+        let start = self.start();
+        let label = |svg: &mut String, x: u32, y: u32, text: &str| {
+            let _ = writeln!(
+                svg,
+                "<text x=\"{}\" y=\"{}\" font-size=\"10\" {} fill=\"{}\">{}</text>",
+                x, y, font, self.theme.text, text
+            );
+        };
+
+        let mut previous = None;
+        for week in 0..self.weeks() {
+            let sunday = start + chrono::Duration::weeks(i64::from(week));
+            if previous != Some(sunday.month()) && week + 3 < self.weeks() {
+                label(
+                    &mut svg,
+                    left + week * STEP,
+                    top - 6,
+                    &sunday.format("%b").to_string(),
+                );
+            }
+            previous = Some(sunday.month());
+        }
+        for (row, name) in [(1, "Mon"), (3, "Wed"), (5, "Fri")] {
+            label(&mut svg, PADDING, top + row * STEP + CELL - 1, name);
+        }
+
+        for date in start.iter_days().take_while(|d| *d <= self.to) {
+            if date < self.from {
+                continue;
+            }
+            let day = match self.calendar.get(date) {
+                Some(day) => day,
+                None => continue,
+            };
+            let offset = (date - start).num_days() as u32;
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{c}\" height=\"{c}\" rx=\"2\" fill=\"{}\"><title>{} contributions on {}</title></rect>",
+                left + offset / 7 * STEP,
+                top + offset % 7 * STEP,
+                self.theme.scale[usize::from(day.level.min(4))],
+                day.count,
+                date,
+                c = CELL
+            );
+        }
+
+        let legend_y = top + 7 * STEP + 8;
+        let legend_x = width - PADDING - 5 * STEP - 28;
+        label(&mut svg, legend_x - 30, legend_y + CELL - 1, "Less");
+        for (i, color) in self.theme.scale.iter().enumerate() {
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{c}\" height=\"{c}\" rx=\"2\" fill=\"{}\"/>",
+                legend_x + i as u32 * STEP,
+                legend_y,
+                color,
+                c = CELL
+            );
+        }
+        label(
+            &mut svg,
+            legend_x + 5 * STEP + 2,
+            legend_y + CELL - 1,
+            "More",
+        );
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// The image rasterized to PNG. Text is drawn with the fonts installed on the system.
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        let render_error = |e: &dyn std::fmt::Display| ContriviewError::Output(e.to_string());
+
+        let mut options = resvg::usvg::Options::default();
+        options.fontdb_mut().load_system_fonts();
+        let tree =
+            resvg::usvg::Tree::from_str(&self.to_svg(), &options).map_err(|e| render_error(&e))?;
+
+        let size = tree.size().to_int_size();
+        let mut pixmap = resvg::tiny_skia::Pixmap::new(size.width(), size.height())
+            .ok_or_else(|| render_error(&"empty image"))?;
+        resvg::render(
+            &tree,
+            resvg::tiny_skia::Transform::default(),
+            &mut pixmap.as_mut(),
+        );
+
+        pixmap.encode_png().map_err(|e| render_error(&e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{date, sample_calendar};
+
+    #[test]
+    fn test_themes() {
+        assert_eq!("#c6e48b", Theme::default().scale[1]);
+        assert_eq!("#0d1117", Theme::named("dark").unwrap().background);
+        assert_eq!(None, Theme::named("solarized"));
+
+        let theme = Theme::default()
+            .with_scale("#000000, #111111,#222222,#333333,#FFFFFF")
+            .unwrap();
+        assert_eq!("#FFFFFF", theme.scale[4]);
+        assert_eq!("#ffffff", theme.background);
+
+        for colors in &["#000000", "#000000,#111111,#222222,#333333,red", ""] {
+            assert!(matches!(
+                Theme::default().with_scale(colors),
+                Err(ContriviewError::InvalidColor(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_svg() {
+        let calendar = sample_calendar();
+        let view = ContriView::from_calendar(&calendar, date("2019-01-26"));
+        let svg = Export::new(&calendar, date("2018-01-28"), date("2019-01-26"))
+            .with_theme(Theme::named("dark").unwrap())
+            .with_title("k-nasa <& friends>")
+            .with_summary(&view)
+            .to_svg();
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"738\""));
+        assert!(svg.contains(">k-nasa &lt;&amp; friends&gt;</text>"));
+        assert!(svg.contains(
+            ">3532 contributions, 9.52 a day, longest streak 50 days, current streak 1 day</text>"
+        ));
+        assert!(svg.contains("<title>3 contributions on 2019-01-26</title>"));
+        assert!(!svg.contains("on 2018-01-27<"));
+        assert!(svg.contains("fill=\"#0d1117\""));
+        // One rect per drawn day, the legend and the background.
+        assert_eq!(364 + 5 + 1, svg.matches("<rect").count());
+    }
+
+    #[test]
+    fn test_crop() {
+        let calendar = sample_calendar();
+        let export = Export::new(&calendar, date("2018-12-01"), date("2018-12-31"));
+        let svg = export.to_svg();
+
+        assert_eq!(31 + 5 + 1, svg.matches("<rect").count());
+        assert_eq!((161, 163), export.size());
+        assert!(!svg.contains("<text x=\"16\" y=\"30\""));
+    }
+
+    #[test]
+    fn test_crop_with_summary() {
+        let calendar = sample_calendar();
+        let view = ContriView::from_calendar(&calendar, date("2018-12-31"));
+        let export = Export::new(&calendar, date("2018-12-01"), date("2018-12-31"))
+            .with_title("k-nasa")
+            .with_summary(&view);
+        let (width, height) = export.size();
+        let (summary, size) = export.header_lines().pop().unwrap();
+
+        // The summary, not the five weeks, sets the width.
+        assert_eq!(PADDING * 2 + text_width(&summary, size), width);
+        assert!(width > 161);
+        assert_eq!(163 + 2 * LINE, height);
+        assert!(export.to_svg().starts_with(&format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\"",
+            width
+        )));
+    }
+
+    #[test]
+    fn test_png() {
+        let calendar = sample_calendar();
+        let png = Export::new(&calendar, date("2018-12-01"), date("2018-12-31"))
+            .to_png()
+            .unwrap();
+
+        assert_eq!(b"\x89PNG\r\n\x1a\n", &png[..8]);
+        // Width and height in the IHDR chunk.
+        assert_eq!(161u32.to_be_bytes(), png[16..20]);
+        assert_eq!(163u32.to_be_bytes(), png[20..24]);
+    }
+}
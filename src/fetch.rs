@@ -0,0 +1,274 @@
+//! Downloading contribution calendars over HTTP.
+
+use crate::calendar::{ContributionCalendar, DateRange};
+use crate::error::{ContriviewError, Result};
+use crate::http::HttpClient;
+use crate::source::{ContributionSource, Fetched, Validators};
+use chrono::prelude::*;
+use reqwest::header::{HeaderMap, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+
+/// Host used when `--host` is not given.
+pub const GITHUB_HOST: &str = "https://github.com";
+
+/// Returns the URL of the contribution calendar of `username`.
+pub fn contributions_url(username: &str) -> String {
+    GithubHtmlSource::new().contributions_url(username)
+}
+
+/// Downloads the contribution calendar page of `username`.
+pub fn fetch_html(username: &str) -> Result<String> {
+    GithubHtmlSource::new().fetch_html(username)
+}
+
+/// GETs `url` on behalf of `username`, mapping a 404 to [`ContriviewError::UserNotFound`].
+pub(crate) fn get_text(http: &HttpClient, url: &str, username: &str) -> Result<String> {
+    // Without validators there is nothing the server could answer 304 to.
+    let fetched = get_text_if_modified(http, url, username, &Validators::default())?;
+
+    Ok(fetched.map(|(text, _)| text).unwrap_or_default())
+}
+
+/// Like [`get_text`], but sends `validators` as a conditional request.
+///
+/// Returns `None` if the server answered 304 Not Modified, otherwise the body and its validators.
+pub(crate) fn get_text_if_modified(
+    http: &HttpClient,
+    url: &str,
+    username: &str,
+    validators: &Validators,
+) -> Result<Option<(String, Validators)>> {
+    let resp = http.execute(url, |client| {
+        let mut request = client.get(url);
+        if let Some(etag) = &validators.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(date) = &validators.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, date);
+        }
+        request
+    })?;
+
+    if resp.status() == StatusCode::NOT_MODIFIED && !validators.is_empty() {
+        return Ok(None);
+    }
+    check_status(resp.status(), username, url)?;
+
+    let validators = validators_from_headers(resp.headers());
+    let text = resp.text().map_err(|source| ContriviewError::Network {
+        url: url.to_string(),
+        source,
+    })?;
+
+    Ok(Some((text, validators)))
+}
+
+fn validators_from_headers(headers: &HeaderMap) -> Validators {
+    let header = |name| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from)
+    };
+
+    Validators {
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+    }
+}
+
+/// Turns a `--host` value into a base URL, defaulting to https.
+pub(crate) fn base_url(host: &str) -> String {
+    let host = host.trim_end_matches('/');
+
+    if host.starts_with("http://") || host.starts_with("https://") {
+        host.to_string()
+    } else {
+        format!("https://{}", host)
+    }
+}
+
+/// Scrapes the public contributions page.
+///
+/// Unbounded ranges read the latest year. Bounded ranges are fetched a calendar year at a time
+/// with `?from=&to=` and stitched together.
+#[derive(Debug, Clone)]
+pub struct GithubHtmlSource {
+    host: String,
+    http: HttpClient,
+    today: NaiveDate,
+}
+
+impl GithubHtmlSource {
+    /// A source for github.com.
+    pub fn new() -> Self {
+        GithubHtmlSource {
+            host: GITHUB_HOST.to_string(),
+            http: HttpClient::new(),
+            today: Local::now().date_naive(),
+        }
+    }
+
+    /// Reads from another host instead, e.g. a GitHub Enterprise server.
+    pub fn with_host(mut self, host: &str) -> Self {
+        self.host = base_url(host);
+        self
+    }
+
+    /// Sends the requests through `http`.
+    pub fn with_http(mut self, http: HttpClient) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Takes `today` as the end of open ranges, instead of today in the system timezone.
+    pub fn with_today(mut self, today: NaiveDate) -> Self {
+        self.today = today;
+        self
+    }
+
+    /// Returns the URL of the contribution calendar of `username`.
+    pub fn contributions_url(&self, username: &str) -> String {
+        format!("{}/users/{}/contributions", self.host, username)
+    }
+
+    /// Returns the URL of the contribution calendar of `username` from `from` to `to`.
+    pub fn contributions_url_between(
+        &self,
+        username: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> String {
+        format!(
+            "{}?from={}&to={}",
+            self.contributions_url(username),
+            from,
+            to
+        )
+    }
+
+    /// Downloads the contribution calendar page of `username`.
+    pub fn fetch_html(&self, username: &str) -> Result<String> {
+        get_text(&self.http, &self.contributions_url(username), username)
+    }
+}
+
+impl Default for GithubHtmlSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContributionSource for GithubHtmlSource {
+    fn fetch(&self, username: &str, range: &DateRange) -> Result<ContributionCalendar> {
+        if range.is_unbounded() {
+            return ContributionCalendar::from_html(&self.fetch_html(username)?);
+        }
+
+        let mut windows = vec![];
+        for (from, to) in range.yearly_windows(self.today) {
+            let url = self.contributions_url_between(username, from, to);
+            let calendar = ContributionCalendar::from_html(&get_text(&self.http, &url, username)?)?;
+            windows.push(calendar.within(&DateRange::new(from, to)));
+        }
+
+        Ok(ContributionCalendar::concat(windows))
+    }
+
+    fn cache_id(&self) -> Option<String> {
+        Some(format!("html {}", self.host))
+    }
+
+    fn fetch_if_modified(
+        &self,
+        username: &str,
+        range: &DateRange,
+        validators: &Validators,
+    ) -> Result<Fetched> {
+        // A bounded range takes several requests, which one set of validators cannot cover.
+        if !range.is_unbounded() {
+            return Ok(Fetched::Modified(
+                self.fetch(username, range)?,
+                Validators::default(),
+            ));
+        }
+
+        let url = self.contributions_url(username);
+
+        Ok(
+            match get_text_if_modified(&self.http, &url, username, validators)? {
+                Some((html, validators)) => {
+                    Fetched::Modified(ContributionCalendar::from_html(&html)?, validators)
+                }
+                None => Fetched::NotModified,
+            },
+        )
+    }
+}
+
+fn check_status(status: StatusCode, username: &str, url: &str) -> Result<()> {
+    if status == StatusCode::NOT_FOUND {
+        return Err(ContriviewError::UserNotFound(username.to_string()));
+    }
+
+    if !status.is_success() {
+        return Err(ContriviewError::HttpStatus {
+            url: url.to_string(),
+            status: status.as_u16(),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{sample_calendar, sample_html};
+    use crate::mock_server::{MockServer, Response};
+
+    #[test]
+    fn test_check_status() {
+        let url = contributions_url("k-nasa");
+
+        assert!(check_status(StatusCode::OK, "k-nasa", &url).is_ok());
+        assert!(matches!(
+            check_status(StatusCode::NOT_FOUND, "k-nasa", &url),
+            Err(ContriviewError::UserNotFound(u)) if u == "k-nasa"
+        ));
+        assert!(matches!(
+            check_status(StatusCode::BAD_GATEWAY, "k-nasa", &url),
+            Err(ContriviewError::HttpStatus { status: 502, .. })
+        ));
+    }
+
+    #[test]
+    fn test_fetch_range_by_year() {
+        let server = MockServer::sequence(vec![Response::new(200, sample_html())]);
+        let source = GithubHtmlSource::new().with_host(server.url());
+        let range = DateRange::new(
+            NaiveDate::from_ymd_opt(2018, 12, 25).unwrap(),
+            NaiveDate::from_ymd_opt(2019, 1, 5).unwrap(),
+        );
+
+        let calendar = source.fetch("k-nasa", &range).unwrap();
+
+        assert_eq!(sample_calendar().within(&range), calendar);
+        assert_eq!(12, calendar.days().len());
+        let paths: Vec<_> = server.requests().into_iter().map(|r| r.path).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/users/k-nasa/contributions?from=2018-12-25&to=2018-12-31",
+                "/users/k-nasa/contributions?from=2019-01-01&to=2019-01-05",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_base_url() {
+        assert_eq!("https://gitlab.com", base_url("gitlab.com"));
+        assert_eq!("https://gitlab.com", base_url("https://gitlab.com/"));
+        assert_eq!("http://127.0.0.1:8080", base_url("http://127.0.0.1:8080"));
+    }
+}
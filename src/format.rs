@@ -0,0 +1,639 @@
+//! Machine-readable renderings of a [`ContriView`].
+
+use crate::calendar::{ContributionCalendar, ContributionDay};
+use crate::compare::Comparison;
+use crate::contriview::ContriView;
+use crate::error::{ContriviewError, Result};
+use crate::history::HistorySummary;
+use crate::period::{PeriodSummary, PeriodTable};
+use crate::team::{MemberShare, TeamView};
+use crate::trend::Trend;
+use serde::Serialize;
+
+/// Version of the serialized report layout. Bumped whenever a field is renamed, removed or changes
+/// type.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Output format selected with `--format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The `key: value` block printed by `Display`.
+    Text,
+    /// A JSON document.
+    Json,
+    /// A YAML document.
+    Yaml,
+    /// A TOML document.
+    Toml,
+    /// A header row and a summary row, comma separated.
+    Csv,
+    /// A header row and a summary row, tab separated.
+    Tsv,
+}
+
+impl OutputFormat {
+    /// Names accepted by [`str::parse`].
+    pub const NAMES: &'static [&'static str] = &["text", "json", "yaml", "toml", "csv", "tsv"];
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = ContriviewError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "yaml" => Ok(OutputFormat::Yaml),
+            "toml" => Ok(OutputFormat::Toml),
+            "csv" => Ok(OutputFormat::Csv),
+            "tsv" => Ok(OutputFormat::Tsv),
+            _ => Err(ContriviewError::UnknownFormat(s.to_string())),
+        }
+    }
+}
+
+/// The serialized document: the summary and the calendar it was computed from.
+#[derive(Debug, Serialize)]
+pub struct Report<'a> {
+    /// Always [`SCHEMA_VERSION`].
+    pub schema_version: u32,
+    /// Summary statistics.
+    pub summary: &'a ContriView,
+    /// Daily contributions, oldest first.
+    pub calendar: &'a [ContributionDay],
+}
+
+impl<'a> Report<'a> {
+    /// Creates a report of `summary` computed from `calendar`.
+    pub fn new(summary: &'a ContriView, calendar: &'a ContributionCalendar) -> Self {
+        Report {
+            schema_version: SCHEMA_VERSION,
+            summary,
+            calendar: calendar.days(),
+        }
+    }
+}
+
+/// The serialized document of a multi-user comparison.
+#[derive(Debug, Serialize)]
+pub struct ComparisonReport<'a> {
+    /// Always [`SCHEMA_VERSION`].
+    pub schema_version: u32,
+    /// One summary per user, in display order.
+    pub users: Vec<UserSummary<'a>>,
+}
+
+/// Summary of one user in a [`ComparisonReport`].
+#[derive(Debug, Serialize)]
+pub struct UserSummary<'a> {
+    /// Name of the account.
+    pub username: &'a str,
+    /// Summary statistics.
+    pub summary: &'a ContriView,
+}
+
+impl<'a> ComparisonReport<'a> {
+    /// Creates a report of `comparison`.
+    pub fn new(comparison: &'a Comparison) -> Self {
+        ComparisonReport {
+            schema_version: SCHEMA_VERSION,
+            users: comparison
+                .rows()
+                .iter()
+                .map(|(username, summary)| UserSummary { username, summary })
+                .collect(),
+        }
+    }
+}
+
+/// The serialized document of a team summary.
+#[derive(Debug, Serialize)]
+pub struct TeamReport<'a> {
+    /// Always [`SCHEMA_VERSION`].
+    pub schema_version: u32,
+    /// Name of the team.
+    pub team: &'a str,
+    /// Contributions in the merged calendar divided by the number of members.
+    pub average_per_member: f64,
+    /// Statistics of the merged calendar.
+    pub summary: &'a ContriView,
+    /// Contributions and share of each member.
+    pub members: &'a [MemberShare],
+    /// Merged daily contributions, oldest first.
+    pub calendar: &'a [ContributionDay],
+}
+
+impl<'a> TeamReport<'a> {
+    /// Creates a report of `team`.
+    pub fn new(team: &'a TeamView) -> Self {
+        TeamReport {
+            schema_version: SCHEMA_VERSION,
+            team: team.name(),
+            average_per_member: team.average_per_member(),
+            summary: team.summary(),
+            members: team.members(),
+            calendar: team.calendar().days(),
+        }
+    }
+}
+
+/// The serialized document of a stored history.
+#[derive(Debug, Serialize)]
+pub struct HistoryReport<'a> {
+    /// Always [`SCHEMA_VERSION`].
+    pub schema_version: u32,
+    /// Per-year and all-time totals.
+    pub history: &'a HistorySummary,
+}
+
+/// The serialized document of period summaries.
+#[derive(Debug, Serialize)]
+pub struct PeriodReport<'a> {
+    /// Always [`SCHEMA_VERSION`].
+    pub schema_version: u32,
+    /// Periods of each user, in display order.
+    pub users: Vec<UserPeriods<'a>>,
+}
+
+/// Periods of one user in a [`PeriodReport`].
+#[derive(Debug, Serialize)]
+pub struct UserPeriods<'a> {
+    /// Name of the account.
+    pub username: &'a str,
+    /// One summary per requested period.
+    pub periods: &'a [PeriodSummary],
+}
+
+impl<'a> PeriodReport<'a> {
+    /// Creates a report of `table`.
+    pub fn new(table: &'a PeriodTable) -> Self {
+        PeriodReport {
+            schema_version: SCHEMA_VERSION,
+            users: table
+                .rows()
+                .iter()
+                .map(|(username, periods)| UserPeriods { username, periods })
+                .collect(),
+        }
+    }
+}
+
+/// The serialized document of trends.
+#[derive(Debug, Serialize)]
+pub struct TrendReport<'a> {
+    /// Always [`SCHEMA_VERSION`].
+    pub schema_version: u32,
+    /// One trend per user, in display order.
+    pub trends: &'a [Trend],
+}
+
+/// Leading column of the delimited formats, which cannot express nesting.
+#[derive(Serialize)]
+struct SchemaColumn {
+    schema_version: u32,
+}
+
+/// Renders `report` in `format`.
+pub fn render(report: &Report, format: OutputFormat) -> Result<String> {
+    let schema = || SchemaColumn {
+        schema_version: report.schema_version,
+    };
+
+    match format {
+        OutputFormat::Text => Ok(report.summary.to_string()),
+        OutputFormat::Csv => render_rows(Some((schema(), report.summary)), b','),
+        OutputFormat::Tsv => render_rows(Some((schema(), report.summary)), b'\t'),
+        _ => render_document(report, format),
+    }
+}
+
+/// Renders a comparison in `format`. Text output is the side-by-side table.
+pub fn render_comparison(comparison: &Comparison, format: OutputFormat) -> Result<String> {
+    let report = ComparisonReport::new(comparison);
+    let rows = || {
+        report.users.iter().map(|user| {
+            let columns = UserColumns {
+                schema_version: report.schema_version,
+                username: user.username,
+            };
+            (columns, user.summary)
+        })
+    };
+
+    match format {
+        OutputFormat::Text => Ok(comparison.to_string()),
+        OutputFormat::Csv => render_rows(rows(), b','),
+        OutputFormat::Tsv => render_rows(rows(), b'\t'),
+        _ => render_document(&report, format),
+    }
+}
+
+/// Renders a team summary in `format`. Delimited formats list the members' shares.
+pub fn render_team(team: &TeamView, format: OutputFormat) -> Result<String> {
+    let report = TeamReport::new(team);
+    let rows = || {
+        team.members().iter().map(|member| MemberRow {
+            schema_version: report.schema_version,
+            team: team.name(),
+            username: &member.username,
+            contributions: member.contributions,
+            share: member.share,
+        })
+    };
+
+    match format {
+        OutputFormat::Text => Ok(team.to_string()),
+        OutputFormat::Csv => render_rows(rows(), b','),
+        OutputFormat::Tsv => render_rows(rows(), b'\t'),
+        _ => render_document(&report, format),
+    }
+}
+
+/// Renders a history summary in `format`. Delimited formats list one row per year.
+pub fn render_history(history: &HistorySummary, format: OutputFormat) -> Result<String> {
+    let report = HistoryReport {
+        schema_version: SCHEMA_VERSION,
+        history,
+    };
+    let rows = || {
+        history.years().iter().map(|year| YearRow {
+            schema_version: report.schema_version,
+            username: history.username(),
+            year: year.year,
+            contributions: year.contributions,
+        })
+    };
+
+    match format {
+        OutputFormat::Text => Ok(history.to_string()),
+        OutputFormat::Csv => render_rows(rows(), b','),
+        OutputFormat::Tsv => render_rows(rows(), b'\t'),
+        _ => render_document(&report, format),
+    }
+}
+
+/// Renders period summaries in `format`. Delimited formats list one row per user and period.
+pub fn render_periods(table: &PeriodTable, format: OutputFormat) -> Result<String> {
+    let report = PeriodReport::new(table);
+    let rows = || {
+        table.rows().iter().flat_map(|(username, periods)| {
+            periods.iter().map(move |period| {
+                let columns = UserColumns {
+                    schema_version: SCHEMA_VERSION,
+                    username,
+                };
+                (columns, period)
+            })
+        })
+    };
+
+    match format {
+        OutputFormat::Text => Ok(table.to_string()),
+        OutputFormat::Csv => render_rows(rows(), b','),
+        OutputFormat::Tsv => render_rows(rows(), b'\t'),
+        _ => render_document(&report, format),
+    }
+}
+
+/// Renders trends in `format`. Text output draws the sparklines and bars, delimited formats list
+/// one row per user and bucket.
+pub fn render_trends(trends: &[Trend], format: OutputFormat) -> Result<String> {
+    let report = TrendReport {
+        schema_version: SCHEMA_VERSION,
+        trends,
+    };
+    let rows = || {
+        trends.iter().flat_map(|trend| {
+            trend.buckets().iter().map(move |bucket| {
+                let columns = UserColumns {
+                    schema_version: SCHEMA_VERSION,
+                    username: trend.username(),
+                };
+                (columns, bucket)
+            })
+        })
+    };
+
+    match format {
+        OutputFormat::Text => Ok(trends
+            .iter()
+            .map(Trend::to_string)
+            .collect::<Vec<_>>()
+            .join("\n\n")),
+        OutputFormat::Csv => render_rows(rows(), b','),
+        OutputFormat::Tsv => render_rows(rows(), b'\t'),
+        _ => render_document(&report, format),
+    }
+}
+
+/// Renders the nested formats.
+fn render_document<T: Serialize>(document: &T, format: OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Json => serde_json::to_string_pretty(document).map_err(output_error),
+        OutputFormat::Yaml => serde_yaml::to_string(document).map_err(output_error),
+        // TOML needs plain values before tables, whatever the order of the fields, and an empty
+        // array of tables is a plain value.
+        OutputFormat::Toml => toml::Value::try_from(document)
+            .and_then(|value| toml::to_string(&value))
+            .map_err(output_error),
+        _ => unreachable!("{:?} is not a document format", format),
+    }
+}
+
+/// Renders a header and one line per row.
+fn render_rows<R: Serialize>(rows: impl IntoIterator<Item = R>, delimiter: u8) -> Result<String> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(vec![]);
+
+    for row in rows {
+        writer.serialize(row).map_err(output_error)?;
+    }
+    let bytes = writer.into_inner().map_err(output_error)?;
+
+    String::from_utf8(bytes).map_err(output_error)
+}
+
+fn output_error(e: impl std::fmt::Display) -> ContriviewError {
+    ContriviewError::Output(e.to_string())
+}
+
+#[derive(Serialize)]
+struct UserColumns<'a> {
+    schema_version: u32,
+    username: &'a str,
+}
+
+#[derive(Serialize)]
+struct MemberRow<'a> {
+    schema_version: u32,
+    team: &'a str,
+    username: &'a str,
+    contributions: u32,
+    share: f64,
+}
+
+#[derive(Serialize)]
+struct YearRow<'a> {
+    schema_version: u32,
+    username: &'a str,
+    year: i32,
+    contributions: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::sample_calendar;
+    use crate::period::Period;
+    use crate::trend::Granularity;
+    use chrono::NaiveDate;
+
+    fn render_sample(format: OutputFormat) -> String {
+        let calendar = sample_calendar();
+        let view =
+            ContriView::from_calendar(&calendar, NaiveDate::from_ymd_opt(2019, 1, 26).unwrap());
+
+        render(&Report::new(&view, &calendar), format).unwrap()
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(OutputFormat::Json, "json".parse().unwrap());
+        assert_eq!(OutputFormat::Tsv, "tsv".parse().unwrap());
+        assert!("xml".parse::<OutputFormat>().is_err());
+    }
+
+    #[test]
+    fn test_render_json() {
+        let json: serde_json::Value =
+            serde_json::from_str(&render_sample(OutputFormat::Json)).unwrap();
+
+        assert_eq!(json["schema_version"], SCHEMA_VERSION);
+        assert_eq!(json["summary"]["sum_contributions"], 3532);
+        assert_eq!(json["summary"]["week_contributions"], 51);
+        assert_eq!(json["summary"]["longest_streak"], 50);
+        assert_eq!(json["summary"]["longest_streak_start"], "2018-09-17");
+        assert_eq!(json["calendar"].as_array().unwrap().len(), 371);
+        assert_eq!(
+            json["calendar"][370],
+            serde_json::json!({"date": "2019-01-26", "count": 3, "level": 1})
+        );
+    }
+
+    #[test]
+    fn test_render_yaml() {
+        let yaml: serde_yaml::Value =
+            serde_yaml::from_str(&render_sample(OutputFormat::Yaml)).unwrap();
+
+        assert_eq!(
+            yaml["schema_version"],
+            serde_yaml::Value::from(SCHEMA_VERSION)
+        );
+        assert_eq!(
+            yaml["summary"]["today_contributions"],
+            serde_yaml::Value::from(3)
+        );
+    }
+
+    #[test]
+    fn test_render_toml() {
+        let toml: toml::Value = render_sample(OutputFormat::Toml).parse().unwrap();
+
+        assert_eq!(
+            toml["schema_version"].as_integer(),
+            Some(SCHEMA_VERSION as i64)
+        );
+        assert_eq!(
+            toml["summary"]["month_contributions"].as_integer(),
+            Some(260)
+        );
+        assert_eq!(toml["summary"]["longest_gap"].as_integer(), Some(6));
+        assert_eq!(toml["calendar"].as_array().unwrap().len(), 371);
+    }
+
+    #[test]
+    fn test_render_toml_empty_calendar() {
+        let calendar = ContributionCalendar::default();
+        let view = ContriView::default();
+        let toml: toml::Value = render(&Report::new(&view, &calendar), OutputFormat::Toml)
+            .unwrap()
+            .parse()
+            .unwrap();
+
+        assert_eq!(toml["calendar"].as_array().map(Vec::len), Some(0));
+        assert_eq!(toml["summary"]["sum_contributions"].as_integer(), Some(0));
+    }
+
+    #[test]
+    fn test_render_csv() {
+        assert_eq!(
+            render_sample(OutputFormat::Csv),
+            "schema_version,today_contributions,week_contributions,month_contributions,year_contributions,week_ave,month_ave,sum_ave,sum_contributions,current_streak,longest_streak,longest_streak_start,longest_streak_end,current_gap,longest_gap,median,std_dev,p75,p90,p99,active_day_ave
+2,3,51,260,260,7.285714285714286,10.0,9.520215633423181,3532,1,50,2018-09-17,2018-11-05,0,6,6.0,11.10821535940956,16.0,25.0,44.0,12.70503597122302
+"
+        );
+    }
+
+    fn sample_comparison() -> Comparison {
+        let calendar = sample_calendar();
+        let date = NaiveDate::from_ymd_opt(2019, 1, 26).unwrap();
+
+        Comparison::new(vec![
+            (
+                "k-nasa".to_string(),
+                ContriView::from_calendar(&calendar, date),
+            ),
+            ("nobody".to_string(), ContriView::default()),
+        ])
+    }
+
+    #[test]
+    fn test_render_comparison_json() {
+        let json: serde_json::Value = serde_json::from_str(
+            &render_comparison(&sample_comparison(), OutputFormat::Json).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(json["schema_version"], SCHEMA_VERSION);
+        assert_eq!(json["users"][0]["username"], "k-nasa");
+        assert_eq!(json["users"][0]["summary"]["sum_contributions"], 3532);
+        assert_eq!(json["users"][1]["summary"]["sum_contributions"], 0);
+    }
+
+    #[test]
+    fn test_render_comparison_csv() {
+        assert_eq!(
+            render_comparison(&sample_comparison(), OutputFormat::Csv).unwrap(),
+            "schema_version,username,today_contributions,week_contributions,month_contributions,year_contributions,week_ave,month_ave,sum_ave,sum_contributions,current_streak,longest_streak,longest_streak_start,longest_streak_end,current_gap,longest_gap,median,std_dev,p75,p90,p99,active_day_ave
+2,k-nasa,3,51,260,260,7.285714285714286,10.0,9.520215633423181,3532,1,50,2018-09-17,2018-11-05,0,6,6.0,11.10821535940956,16.0,25.0,44.0,12.70503597122302
+2,nobody,0,0,0,0,0.0,0.0,0.0,0,0,0,,,0,0,0.0,0.0,0.0,0.0,0.0,0.0
+"
+        );
+    }
+
+    #[test]
+    fn test_render_team() {
+        let date = NaiveDate::from_ymd_opt(2019, 1, 26).unwrap();
+        let team = TeamView::new(
+            "solo",
+            &[
+                ("k-nasa".to_string(), sample_calendar()),
+                ("nobody".to_string(), ContributionCalendar::default()),
+            ],
+            date,
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_team(&team, OutputFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["team"], "solo");
+        assert_eq!(json["summary"]["sum_contributions"], 3532);
+        assert_eq!(json["average_per_member"], 1766.0);
+        assert_eq!(json["members"][0]["share"], 100.0);
+
+        assert_eq!(
+            render_team(&team, OutputFormat::Csv).unwrap(),
+            "schema_version,team,username,contributions,share
+2,solo,k-nasa,3532,100.0
+2,solo,nobody,0,0.0
+"
+        );
+
+        let toml: toml::Value = render_team(&team, OutputFormat::Toml)
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(toml["team"].as_str(), Some("solo"));
+        assert_eq!(toml["average_per_member"].as_float(), Some(1766.0));
+        assert_eq!(toml["members"][1]["username"].as_str(), Some("nobody"));
+        // The fields alone are in an order TOML can express.
+        assert!(toml::to_string(&TeamReport::new(&team)).is_ok());
+    }
+
+    #[test]
+    fn test_render_history() {
+        let history = HistorySummary::new("k-nasa", &sample_calendar());
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_history(&history, OutputFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["history"]["total"], 3532);
+        assert_eq!(json["history"]["first_day"], "2018-01-21");
+        assert_eq!(json["history"]["years"][1]["year"], 2019);
+
+        assert_eq!(
+            render_history(&history, OutputFormat::Csv).unwrap(),
+            "schema_version,username,year,contributions
+2,k-nasa,2018,3272
+2,k-nasa,2019,260
+"
+        );
+
+        let toml: toml::Value = render_history(&history, OutputFormat::Toml)
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(toml["history"]["total"].as_integer(), Some(3532));
+        assert_eq!(
+            toml["history"]["years"][0]["contributions"].as_integer(),
+            Some(3272)
+        );
+        // The fields alone are in an order TOML can express.
+        assert!(toml::to_string(&history).is_ok());
+    }
+
+    #[test]
+    fn test_render_periods() {
+        let calendar = sample_calendar();
+        let table = PeriodTable::new(
+            vec![("k-nasa".to_string(), &calendar)],
+            &[Period::LastDays(7), Period::YearToDate],
+            NaiveDate::from_ymd_opt(2019, 1, 26).unwrap(),
+        )
+        .unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_periods(&table, OutputFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["users"][0]["username"], "k-nasa");
+        assert_eq!(json["users"][0]["periods"][1]["period"], "ytd");
+        assert_eq!(json["users"][0]["periods"][1]["sum"], 260);
+
+        assert_eq!(
+            render_periods(&table, OutputFormat::Csv).unwrap(),
+            "schema_version,username,period,from,to,sum,average,active_days
+2,k-nasa,7d,2019-01-20,2019-01-26,51,7.285714285714286,5
+2,k-nasa,ytd,2019-01-01,2019-01-26,260,10.0,20
+"
+        );
+    }
+
+    #[test]
+    fn test_render_trends() {
+        let trends = vec![Trend::new(
+            "k-nasa",
+            &sample_calendar(),
+            Granularity::Monthly,
+            NaiveDate::from_ymd_opt(2018, 12, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2019, 1, 26).unwrap(),
+        )];
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_trends(&trends, OutputFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["trends"][0]["granularity"], "monthly");
+        assert_eq!(json["trends"][0]["buckets"][1]["contributions"], 260);
+
+        assert_eq!(
+            render_trends(&trends, OutputFormat::Csv).unwrap(),
+            "schema_version,username,label,from,to,contributions
+2,k-nasa,2018-12,2018-12-01,2018-12-31,222
+2,k-nasa,2019-01,2019-01-01,2019-01-26,260
+"
+        );
+    }
+
+    #[test]
+    fn test_render_tsv() {
+        assert!(
+            render_sample(OutputFormat::Tsv).starts_with("schema_version\ttoday_contributions\t")
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! Contribution calendars from commits in local git repositories.
+
+use crate::calendar::{relative_level, ContributionCalendar, ContributionDay, DateRange};
+use crate::error::{ContriviewError, Result};
+use crate::source::ContributionSource;
+use chrono::prelude::*;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Counts commits authored by the given emails, one contribution per commit.
+///
+/// Commits are dated by their author date in the author's timezone, as `git log` shows them.
+#[derive(Debug, Clone)]
+pub struct GitSource {
+    repos: Vec<PathBuf>,
+    emails: Vec<String>,
+    today: NaiveDate,
+}
+
+impl GitSource {
+    /// Reads the repositories at `repos`.
+    pub fn new<P: Into<PathBuf>>(repos: impl IntoIterator<Item = P>) -> Self {
+        GitSource {
+            repos: repos.into_iter().map(Into::into).collect(),
+            emails: vec![],
+            today: Local::now().date_naive(),
+        }
+    }
+
+    /// Only counts commits by `emails`. Without emails, each repository's `user.email` is used.
+    pub fn with_emails<S: Into<String>>(mut self, emails: impl IntoIterator<Item = S>) -> Self {
+        self.emails = emails.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Takes `today` as the end of open ranges, instead of today in the system timezone.
+    pub fn with_today(mut self, today: NaiveDate) -> Self {
+        self.today = today;
+        self
+    }
+
+    fn authors(&self, repo: &Path) -> Result<Vec<String>> {
+        if !self.emails.is_empty() {
+            return Ok(self.emails.clone());
+        }
+
+        let email = git(repo, &["config", "user.email"])?;
+        Ok(vec![email.trim().to_string()])
+    }
+}
+
+impl ContributionSource for GitSource {
+    fn fetch(&self, _username: &str, range: &DateRange) -> Result<ContributionCalendar> {
+        let mut counts: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+
+        for repo in &self.repos {
+            let authors = self.authors(repo)?;
+            let log = git(
+                repo,
+                &[
+                    "log",
+                    "--all",
+                    "--no-merges",
+                    "--date=short",
+                    "--format=%ae%x09%ad",
+                ],
+            )?;
+
+            for (email, date) in log.lines().filter_map(|l| l.split_once('\t')) {
+                if !authors.iter().any(|a| a.eq_ignore_ascii_case(email)) {
+                    continue;
+                }
+                if let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+                    *counts.entry(date).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let max = counts.values().copied().max().unwrap_or_default();
+        let days = counts
+            .into_iter()
+            .map(|(date, count)| ContributionDay {
+                date,
+                count,
+                level: relative_level(count, max),
+            })
+            .collect();
+        let (from, to) = range.or_last_year(self.today);
+
+        Ok(ContributionCalendar::new(days).fill(from, to).within(range))
+    }
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<String> {
+    let error = |message: String| ContriviewError::Git {
+        repo: repo.to_path_buf(),
+        message,
+    };
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .map_err(|e| error(e.to_string()))?;
+
+    if !output.status.success() {
+        return Err(error(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{date, TempDir};
+
+    struct Repo(TempDir);
+
+    impl Repo {
+        fn init(name: &str) -> Self {
+            let repo = Repo(TempDir::new(&format!("git-{}", name)));
+            repo.run(&["init", "--quiet"]);
+            repo.run(&["config", "user.email", "nasa@example.com"]);
+            repo.run(&["config", "user.name", "nasa"]);
+            repo
+        }
+
+        fn commit(&self, email: &str, date: &str) {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(self.path())
+                .args(["commit", "--quiet", "--allow-empty", "-m", "work"])
+                .env("GIT_AUTHOR_EMAIL", email)
+                .env("GIT_AUTHOR_DATE", format!("{}T12:00:00+09:00", date))
+                .status()
+                .unwrap();
+            assert!(status.success());
+        }
+
+        fn run(&self, args: &[&str]) {
+            git(self.path(), args).unwrap();
+        }
+
+        fn path(&self) -> &Path {
+            self.0.path()
+        }
+    }
+
+    fn range() -> DateRange {
+        DateRange::new(date("2019-01-01"), date("2019-01-03"))
+    }
+
+    fn counts(calendar: &ContributionCalendar) -> Vec<u32> {
+        calendar.days().iter().map(|d| d.count).collect()
+    }
+
+    #[test]
+    fn test_fetch_with_configured_email() {
+        let repo = Repo::init("configured");
+        repo.commit("nasa@example.com", "2019-01-01");
+        repo.commit("NASA@example.com", "2019-01-01");
+        repo.commit("someone@example.com", "2019-01-02");
+        repo.commit("nasa@example.com", "2019-01-03");
+
+        let calendar = GitSource::new(vec![repo.path()])
+            .fetch("", &range())
+            .unwrap();
+
+        assert_eq!(vec![2, 0, 1], counts(&calendar));
+        assert_eq!(4, calendar.get(date("2019-01-01")).unwrap().level);
+    }
+
+    #[test]
+    fn test_fetch_across_repositories() {
+        let first = Repo::init("first");
+        let second = Repo::init("second");
+        first.commit("nasa@example.com", "2019-01-02");
+        second.commit("someone@example.com", "2019-01-02");
+        second.commit("nasa@example.com", "2019-01-03");
+
+        let calendar = GitSource::new(vec![first.path(), second.path()])
+            .with_emails(vec!["nasa@example.com", "someone@example.com"])
+            .fetch("", &range())
+            .unwrap();
+
+        assert_eq!(vec![0, 2, 1], counts(&calendar));
+    }
+
+    #[test]
+    fn test_fetch_not_a_repository() {
+        let dir = TempDir::new("git-none");
+
+        let result = GitSource::new(vec![dir.path()])
+            .with_emails(vec!["nasa@example.com"])
+            .fetch("", &range());
+
+        assert!(matches!(result, Err(ContriviewError::Git { .. })));
+    }
+}
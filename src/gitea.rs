@@ -0,0 +1,275 @@
+//! Contribution heatmaps from Gitea, Forgejo and Codeberg.
+
+use crate::calendar::{relative_level, ContributionCalendar, ContributionDay, DateRange};
+use crate::error::{ContriviewError, Result};
+use crate::fetch::{base_url, get_text, get_text_if_modified};
+use crate::http::HttpClient;
+use crate::source::{ContributionSource, Fetched, Validators};
+use chrono::prelude::*;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Base URL used when `--host` is not given.
+pub const CODEBERG_HOST: &str = "https://codeberg.org";
+
+/// Reads `/api/v1/users/{user}/heatmap`, a list of timestamped contribution counts.
+#[derive(Debug, Clone)]
+pub struct GiteaSource {
+    host: String,
+    http: HttpClient,
+    today: NaiveDate,
+    timezone: Option<chrono_tz::Tz>,
+}
+
+impl GiteaSource {
+    /// A source for Codeberg.
+    pub fn new() -> Self {
+        GiteaSource {
+            host: CODEBERG_HOST.to_string(),
+            http: HttpClient::new(),
+            today: Local::now().date_naive(),
+            timezone: None,
+        }
+    }
+
+    /// Reads from another instance instead, e.g. `git.example.com`.
+    pub fn with_host(mut self, host: &str) -> Self {
+        self.host = base_url(host);
+        self
+    }
+
+    /// Sends the requests through `http`.
+    pub fn with_http(mut self, http: HttpClient) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Takes `today` as the last day the server keeps, instead of today in the system timezone.
+    pub fn with_today(mut self, today: NaiveDate) -> Self {
+        self.today = today;
+        self
+    }
+
+    /// Dates the heatmap entries in `timezone` instead of the system timezone.
+    pub fn with_timezone(mut self, timezone: chrono_tz::Tz) -> Self {
+        self.timezone = Some(timezone);
+        self
+    }
+
+    /// Returns the URL of the heatmap of `username`.
+    pub fn heatmap_url(&self, username: &str) -> String {
+        format!("{}/api/v1/users/{}/heatmap", self.host, username)
+    }
+}
+
+impl Default for GiteaSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContributionSource for GiteaSource {
+    fn fetch(&self, username: &str, range: &DateRange) -> Result<ContributionCalendar> {
+        let (from, to) = range.within_last_year(self.today, "Gitea")?;
+        let json = get_text(&self.http, &self.heatmap_url(username), username)?;
+
+        calendar_in_range(&json, from, to, self.timezone)
+    }
+
+    fn cache_id(&self) -> Option<String> {
+        Some(format!("gitea {}", self.host))
+    }
+
+    fn fetch_if_modified(
+        &self,
+        username: &str,
+        range: &DateRange,
+        validators: &Validators,
+    ) -> Result<Fetched> {
+        let (from, to) = range.within_last_year(self.today, "Gitea")?;
+        let url = self.heatmap_url(username);
+
+        Ok(
+            match get_text_if_modified(&self.http, &url, username, validators)? {
+                Some((json, validators)) => Fetched::Modified(
+                    calendar_in_range(&json, from, to, self.timezone)?,
+                    validators,
+                ),
+                None => Fetched::NotModified,
+            },
+        )
+    }
+}
+
+/// Parses the response, dated in `timezone` or the system one, and fills the days without
+/// contributions from `from` to `to`.
+fn calendar_in_range(
+    json: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+    timezone: Option<chrono_tz::Tz>,
+) -> Result<ContributionCalendar> {
+    let calendar = match timezone {
+        Some(tz) => calendar_from_heatmap(json, &tz)?,
+        None => calendar_from_heatmap(json, &Local)?,
+    };
+
+    Ok(calendar.fill(from, to).within(&DateRange::new(from, to)))
+}
+
+#[derive(Deserialize)]
+struct HeatmapEntry {
+    timestamp: i64,
+    contributions: u32,
+}
+
+/// Sums the heatmap entries into days of `tz`.
+fn calendar_from_heatmap<Tz: TimeZone>(json: &str, tz: &Tz) -> Result<ContributionCalendar> {
+    let entries: Vec<HeatmapEntry> = serde_json::from_str(json)
+        .map_err(|e| ContriviewError::MarkupChanged(format!("unexpected Gitea heatmap: {}", e)))?;
+
+    let mut counts = BTreeMap::new();
+    for entry in entries {
+        let date = tz
+            .timestamp_opt(entry.timestamp, 0)
+            .single()
+            .ok_or_else(|| {
+                ContriviewError::MarkupChanged(format!("invalid timestamp {}", entry.timestamp))
+            })?
+            .date_naive();
+        *counts.entry(date).or_insert(0) += entry.contributions;
+    }
+
+    let max = counts.values().copied().max().unwrap_or_default();
+    let days = counts
+        .into_iter()
+        .map(|(date, count)| ContributionDay {
+            date,
+            count,
+            level: relative_level(count, max),
+        })
+        .collect();
+
+    Ok(ContributionCalendar::new(days))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::date;
+    use crate::mock_server::{MockServer, Response};
+
+    // 2019-01-01T23:00:00Z, 2019-01-01T23:45:00Z and 2019-01-02T10:00:00Z.
+    const HEATMAP: &str = r#"[
+  {"timestamp": 1546383600, "contributions": 2},
+  {"timestamp": 1546386300, "contributions": 1},
+  {"timestamp": 1546423200, "contributions": 5}
+]"#;
+
+    fn counts(calendar: &ContributionCalendar) -> Vec<(NaiveDate, u32)> {
+        calendar.days().iter().map(|d| (d.date, d.count)).collect()
+    }
+
+    #[test]
+    fn test_calendar_from_heatmap_utc() {
+        let calendar = calendar_from_heatmap(HEATMAP, &Utc).unwrap();
+
+        assert_eq!(
+            vec![(date("2019-01-01"), 3), (date("2019-01-02"), 5)],
+            counts(&calendar)
+        );
+        assert_eq!(4, calendar.get(date("2019-01-02")).unwrap().level);
+    }
+
+    #[test]
+    fn test_calendar_from_heatmap_local_days() {
+        let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+
+        let calendar = calendar_from_heatmap(HEATMAP, &tokyo).unwrap();
+
+        assert_eq!(vec![(date("2019-01-02"), 8)], counts(&calendar));
+    }
+
+    #[test]
+    fn test_fetch() {
+        let server = MockServer::sequence(vec![Response::new(200, "[]")]);
+        let source = GiteaSource::new()
+            .with_host(server.url())
+            .with_today(date("2019-01-31"));
+
+        let calendar = source
+            .fetch(
+                "k-nasa",
+                &DateRange::new(date("2019-01-01"), date("2019-01-07")),
+            )
+            .unwrap();
+
+        assert_eq!(7, calendar.days().len());
+        assert_eq!("/api/v1/users/k-nasa/heatmap", server.requests()[0].path);
+    }
+
+    #[test]
+    fn test_fetch_in_timezone() {
+        let server = MockServer::sequence(vec![Response::new(200, HEATMAP)]);
+        let source = GiteaSource::new()
+            .with_host(server.url())
+            .with_today(date("2019-01-31"))
+            .with_timezone(chrono_tz::Asia::Tokyo);
+
+        let calendar = source
+            .fetch(
+                "k-nasa",
+                &DateRange::new(date("2019-01-01"), date("2019-01-02")),
+            )
+            .unwrap();
+
+        assert_eq!(
+            vec![(date("2019-01-01"), 0), (date("2019-01-02"), 8)],
+            counts(&calendar)
+        );
+    }
+
+    #[test]
+    fn test_fetch_up_to_past_date() {
+        let server = MockServer::sequence(vec![Response::new(200, "[]")]);
+        let source = GiteaSource::new()
+            .with_host(server.url())
+            .with_today(date("2019-01-31"));
+        let range = DateRange {
+            from: None,
+            to: Some(date("2019-01-10")),
+        };
+
+        let calendar = source.fetch("k-nasa", &range).unwrap();
+
+        let days = calendar.days();
+        assert_eq!(345, days.len());
+        assert_eq!(date("2018-01-31"), days[0].date);
+        assert_eq!(date("2019-01-10"), days[days.len() - 1].date);
+    }
+
+    #[test]
+    fn test_fetch_before_last_year() {
+        let server = MockServer::sequence(vec![]);
+        let source = GiteaSource::new()
+            .with_host(server.url())
+            .with_today(date("2019-01-31"));
+
+        assert!(matches!(
+            source.fetch("k-nasa", &DateRange::year(2017).unwrap()),
+            Err(ContriviewError::OutOfWindow { .. })
+        ));
+        assert!(server.requests().is_empty());
+    }
+
+    #[test]
+    fn test_fetch_user_not_found() {
+        let server = MockServer::sequence(vec![Response::new(404, "")]);
+        let source = GiteaSource::new().with_host(server.url());
+
+        assert!(matches!(
+            source.fetch("nobody", &DateRange::default()),
+            Err(ContriviewError::UserNotFound(_))
+        ));
+    }
+}
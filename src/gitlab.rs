@@ -0,0 +1,218 @@
+//! Contribution calendars from GitLab.
+
+use crate::calendar::{ContributionCalendar, ContributionDay, DateRange};
+use crate::error::{ContriviewError, Result};
+use crate::fetch::{base_url, get_text, get_text_if_modified};
+use crate::http::HttpClient;
+use crate::source::{ContributionSource, Fetched, Validators};
+use chrono::prelude::*;
+use std::collections::BTreeMap;
+
+/// Host used when `--host` is not given.
+pub const GITLAB_HOST: &str = "https://gitlab.com";
+
+/// Reads `/users/<name>/calendar.json`, a map of dates to counts covering the last year.
+#[derive(Debug, Clone)]
+pub struct GitlabSource {
+    host: String,
+    http: HttpClient,
+    today: NaiveDate,
+}
+
+impl GitlabSource {
+    /// A source for gitlab.com.
+    pub fn new() -> Self {
+        GitlabSource {
+            host: GITLAB_HOST.to_string(),
+            http: HttpClient::new(),
+            today: Local::now().date_naive(),
+        }
+    }
+
+    /// Reads from a self-managed instance instead, e.g. `gitlab.example.com`.
+    pub fn with_host(mut self, host: &str) -> Self {
+        self.host = base_url(host);
+        self
+    }
+
+    /// Sends the requests through `http`.
+    pub fn with_http(mut self, http: HttpClient) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Takes `today` as the last day the server keeps, instead of today in the system timezone.
+    pub fn with_today(mut self, today: NaiveDate) -> Self {
+        self.today = today;
+        self
+    }
+
+    /// Returns the URL of the calendar of `username`.
+    pub fn calendar_url(&self, username: &str) -> String {
+        format!("{}/users/{}/calendar.json", self.host, username)
+    }
+}
+
+impl Default for GitlabSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContributionSource for GitlabSource {
+    fn fetch(&self, username: &str, range: &DateRange) -> Result<ContributionCalendar> {
+        let (from, to) = range.within_last_year(self.today, "GitLab")?;
+        let json = get_text(&self.http, &self.calendar_url(username), username)?;
+
+        calendar_in_range(&json, from, to)
+    }
+
+    fn cache_id(&self) -> Option<String> {
+        Some(format!("gitlab {}", self.host))
+    }
+
+    fn fetch_if_modified(
+        &self,
+        username: &str,
+        range: &DateRange,
+        validators: &Validators,
+    ) -> Result<Fetched> {
+        let (from, to) = range.within_last_year(self.today, "GitLab")?;
+        let url = self.calendar_url(username);
+
+        Ok(
+            match get_text_if_modified(&self.http, &url, username, validators)? {
+                Some((json, validators)) => {
+                    Fetched::Modified(calendar_in_range(&json, from, to)?, validators)
+                }
+                None => Fetched::NotModified,
+            },
+        )
+    }
+}
+
+/// Parses the response and fills the days without contributions from `from` to `to`.
+fn calendar_in_range(json: &str, from: NaiveDate, to: NaiveDate) -> Result<ContributionCalendar> {
+    Ok(calendar_from_json(json)?
+        .fill(from, to)
+        .within(&DateRange::new(from, to)))
+}
+
+fn calendar_from_json(json: &str) -> Result<ContributionCalendar> {
+    let counts: BTreeMap<NaiveDate, u32> = serde_json::from_str(json).map_err(|e| {
+        ContriviewError::MarkupChanged(format!("unexpected GitLab calendar: {}", e))
+    })?;
+
+    let days = counts
+        .into_iter()
+        .map(|(date, count)| ContributionDay {
+            date,
+            count,
+            level: level_from_count(count),
+        })
+        .collect();
+
+    Ok(ContributionCalendar::new(days))
+}
+
+/// The thresholds GitLab uses to shade its own calendar.
+fn level_from_count(count: u32) -> u8 {
+    match count {
+        0 => 0,
+        1..=9 => 1,
+        10..=19 => 2,
+        20..=29 => 3,
+        _ => 4,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::date;
+    use crate::mock_server::{MockServer, Response};
+
+    #[test]
+    fn test_fetch() {
+        let server = MockServer::sequence(vec![Response::new(
+            200,
+            r#"{"2019-01-02": 4, "2019-01-04": 25}"#,
+        )]);
+        let source = GitlabSource::new()
+            .with_host(server.url())
+            .with_today(date("2019-01-31"));
+
+        let calendar = source
+            .fetch(
+                "k-nasa",
+                &DateRange::new(date("2019-01-01"), date("2019-01-05")),
+            )
+            .unwrap();
+
+        assert_eq!(
+            vec![(0, 0), (4, 1), (0, 0), (25, 3), (0, 0)],
+            calendar
+                .days()
+                .iter()
+                .map(|d| (d.count, d.level))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!("/users/k-nasa/calendar.json", server.requests()[0].path);
+    }
+
+    #[test]
+    fn test_fetch_up_to_past_date() {
+        let server = MockServer::sequence(vec![Response::new(
+            200,
+            r#"{"2018-01-31": 4, "2019-01-04": 25}"#,
+        )]);
+        let source = GitlabSource::new()
+            .with_host(server.url())
+            .with_today(date("2019-01-31"));
+        let range = DateRange {
+            from: None,
+            to: Some(date("2019-01-10")),
+        };
+
+        let calendar = source.fetch("k-nasa", &range).unwrap();
+
+        let days = calendar.days();
+        assert_eq!(345, days.len());
+        assert_eq!(date("2018-01-31"), days[0].date);
+        assert_eq!(date("2019-01-10"), days[days.len() - 1].date);
+        assert_eq!(29, days.iter().map(|d| d.count).sum::<u32>());
+    }
+
+    #[test]
+    fn test_fetch_before_last_year() {
+        let server = MockServer::sequence(vec![]);
+        let source = GitlabSource::new()
+            .with_host(server.url())
+            .with_today(date("2019-01-31"));
+
+        assert!(matches!(
+            source.fetch("k-nasa", &DateRange::year(2017).unwrap()),
+            Err(ContriviewError::OutOfWindow { .. })
+        ));
+        assert!(server.requests().is_empty());
+    }
+
+    #[test]
+    fn test_fetch_user_not_found() {
+        let server = MockServer::sequence(vec![Response::new(404, "")]);
+        let source = GitlabSource::new().with_host(server.url());
+
+        assert!(matches!(
+            source.fetch("nobody", &DateRange::default()),
+            Err(ContriviewError::UserNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_calendar_from_json_invalid() {
+        assert!(matches!(
+            calendar_from_json("<html>sign in</html>"),
+            Err(ContriviewError::MarkupChanged(_))
+        ));
+    }
+}
@@ -0,0 +1,391 @@
+//! Contribution calendars from the GitHub GraphQL API.
+
+use crate::calendar::{ContributionCalendar, ContributionDay, DateRange};
+use crate::error::{ContriviewError, Result};
+use crate::http::HttpClient;
+use crate::source::ContributionSource;
+use chrono::prelude::*;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Endpoint of the public GitHub GraphQL API.
+pub const GITHUB_GRAPHQL_URL: &str = "https://api.github.com/graphql";
+
+const CALENDAR_QUERY: &str = "query($login: String!, $from: DateTime, $to: DateTime) {
+  user(login: $login) {
+    contributionsCollection(from: $from, to: $to) {
+      contributionCalendar {
+        weeks {
+          contributionDays {
+            date
+            contributionCount
+            contributionLevel
+          }
+        }
+      }
+    }
+  }
+}";
+
+/// Fetches `contributionsCollection.contributionCalendar` with a personal access token.
+///
+/// As a [`ContributionSource`], bounded ranges are queried a calendar year at a time, the
+/// longest span the API accepts.
+#[derive(Debug, Clone)]
+pub struct GraphqlClient {
+    endpoint: String,
+    token: String,
+    http: HttpClient,
+    today: NaiveDate,
+}
+
+impl GraphqlClient {
+    /// Creates a client for [`GITHUB_GRAPHQL_URL`].
+    pub fn new(token: &str) -> Self {
+        GraphqlClient {
+            endpoint: GITHUB_GRAPHQL_URL.to_string(),
+            token: token.to_string(),
+            http: HttpClient::new(),
+            today: Local::now().date_naive(),
+        }
+    }
+
+    /// Sends the queries to `endpoint` instead, e.g. a GitHub Enterprise server.
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_string();
+        self
+    }
+
+    /// Sends the requests through `http`.
+    pub fn with_http(mut self, http: HttpClient) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Takes `today` as the end of open ranges, instead of today in the system timezone.
+    pub fn with_today(mut self, today: NaiveDate) -> Self {
+        self.today = today;
+        self
+    }
+
+    /// Fetches the calendar of `username`. Without bounds, GitHub returns the last year.
+    pub fn fetch_calendar(
+        &self,
+        username: &str,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<ContributionCalendar> {
+        let body = json!({
+            "query": CALENDAR_QUERY,
+            "variables": {
+                "login": username,
+                "from": from.map(|d| format!("{}T00:00:00Z", d)),
+                "to": to.map(|d| format!("{}T23:59:59Z", d)),
+            },
+        });
+
+        let resp = self.http.execute(&self.endpoint, |client| {
+            client
+                .post(&self.endpoint)
+                .bearer_auth(&self.token)
+                .header(reqwest::header::USER_AGENT, "contriview")
+                .json(&body)
+        })?;
+
+        let status = resp.status();
+        if status != StatusCode::OK {
+            return Err(ContriviewError::HttpStatus {
+                url: self.endpoint.clone(),
+                status: status.as_u16(),
+            });
+        }
+
+        let text = resp.text().map_err(|source| ContriviewError::Network {
+            url: self.endpoint.clone(),
+            source,
+        })?;
+        calendar_from_response(&text, username)
+    }
+}
+
+impl ContributionSource for GraphqlClient {
+    fn fetch(&self, username: &str, range: &DateRange) -> Result<ContributionCalendar> {
+        if range.is_unbounded() {
+            return self.fetch_calendar(username, None, None);
+        }
+
+        let mut windows = vec![];
+        for (from, to) in range.yearly_windows(self.today) {
+            windows.push(self.fetch_calendar(username, Some(from), Some(to))?);
+        }
+
+        Ok(ContributionCalendar::concat(windows))
+    }
+
+    /// Tokens can see different calendars, e.g. with private contributions, so the id tells
+    /// them apart by a hash rather than the token itself, which would end up in file names.
+    fn cache_id(&self) -> Option<String> {
+        let mut hasher = DefaultHasher::new();
+        self.token.hash(&mut hasher);
+
+        Some(format!(
+            "graphql {} {:016x}",
+            self.endpoint,
+            hasher.finish()
+        ))
+    }
+}
+
+#[derive(Deserialize)]
+struct Response {
+    data: Option<Data>,
+    #[serde(default)]
+    errors: Vec<GraphqlError>,
+}
+
+#[derive(Deserialize)]
+struct GraphqlError {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct Data {
+    user: Option<User>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct User {
+    contributions_collection: Collection,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Collection {
+    contribution_calendar: Calendar,
+}
+
+#[derive(Deserialize)]
+struct Calendar {
+    weeks: Vec<Week>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Week {
+    contribution_days: Vec<Day>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Day {
+    date: NaiveDate,
+    contribution_count: u32,
+    contribution_level: String,
+}
+
+/// Converts a GraphQL response body into a calendar.
+fn calendar_from_response(body: &str, username: &str) -> Result<ContributionCalendar> {
+    let response: Response = serde_json::from_str(body).map_err(|e| {
+        ContriviewError::MarkupChanged(format!("unexpected GraphQL response: {}", e))
+    })?;
+
+    if let Some(e) = response.errors.first() {
+        if e.kind.as_deref() == Some("NOT_FOUND") {
+            return Err(ContriviewError::UserNotFound(username.to_string()));
+        }
+        return Err(ContriviewError::Api(e.message.clone()));
+    }
+
+    let user = response
+        .data
+        .and_then(|d| d.user)
+        .ok_or_else(|| ContriviewError::UserNotFound(username.to_string()))?;
+
+    let days = user
+        .contributions_collection
+        .contribution_calendar
+        .weeks
+        .into_iter()
+        .flat_map(|w| w.contribution_days)
+        .map(|d| ContributionDay {
+            date: d.date,
+            count: d.contribution_count,
+            level: level_from_name(&d.contribution_level),
+        })
+        .collect();
+
+    Ok(ContributionCalendar::new(days))
+}
+
+fn level_from_name(level: &str) -> u8 {
+    match level {
+        "FIRST_QUARTILE" => 1,
+        "SECOND_QUARTILE" => 2,
+        "THIRD_QUARTILE" => 3,
+        "FOURTH_QUARTILE" => 4,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::date;
+    use crate::mock_server::{MockServer, Response};
+
+    const CALENDAR_RESPONSE: &str = r#"{
+  "data": {
+    "user": {
+      "contributionsCollection": {
+        "contributionCalendar": {
+          "weeks": [
+            {
+              "contributionDays": [
+                {"date": "2019-01-20", "contributionCount": 0, "contributionLevel": "NONE"},
+                {"date": "2019-01-21", "contributionCount": 12, "contributionLevel": "SECOND_QUARTILE"}
+              ]
+            },
+            {
+              "contributionDays": [
+                {"date": "2019-01-27", "contributionCount": 40, "contributionLevel": "FOURTH_QUARTILE"}
+              ]
+            }
+          ]
+        }
+      }
+    }
+  }
+}"#;
+
+    #[test]
+    fn test_fetch_calendar() {
+        let server = MockServer::sequence(vec![Response::new(200, CALENDAR_RESPONSE)]);
+        let endpoint = format!("{}/graphql", server.url());
+        let client = GraphqlClient::new("secret").with_endpoint(&endpoint);
+
+        let calendar = client
+            .fetch_calendar("k-nasa", Some(date("2019-01-20")), Some(date("2019-01-27")))
+            .unwrap();
+
+        assert_eq!(
+            calendar.days(),
+            &[
+                ContributionDay {
+                    date: date("2019-01-20"),
+                    count: 0,
+                    level: 0,
+                },
+                ContributionDay {
+                    date: date("2019-01-21"),
+                    count: 12,
+                    level: 2,
+                },
+                ContributionDay {
+                    date: date("2019-01-27"),
+                    count: 40,
+                    level: 4,
+                },
+            ]
+        );
+
+        let requests = server.requests();
+        assert_eq!(1, requests.len());
+        assert_eq!("POST", requests[0].method);
+        assert_eq!("/graphql", requests[0].path);
+        assert_eq!(Some("Bearer secret"), requests[0].header("authorization"));
+
+        let body: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+        assert_eq!("k-nasa", body["variables"]["login"]);
+        assert_eq!("2019-01-20T00:00:00Z", body["variables"]["from"]);
+        assert_eq!("2019-01-27T23:59:59Z", body["variables"]["to"]);
+    }
+
+    #[test]
+    fn test_fetch_range_by_year() {
+        let server = MockServer::sequence(vec![Response::new(200, CALENDAR_RESPONSE)]);
+        let client = GraphqlClient::new("secret").with_endpoint(server.url());
+
+        client
+            .fetch(
+                "k-nasa",
+                &DateRange::new(date("2017-06-01"), date("2019-01-27")),
+            )
+            .unwrap();
+
+        let windows: Vec<_> = server
+            .requests()
+            .iter()
+            .map(|r| {
+                let body: serde_json::Value = serde_json::from_str(&r.body).unwrap();
+                let variables = &body["variables"];
+                (variables["from"].clone(), variables["to"].clone())
+            })
+            .collect();
+        assert_eq!(
+            windows,
+            vec![
+                (json!("2017-06-01T00:00:00Z"), json!("2017-12-31T23:59:59Z")),
+                (json!("2018-01-01T00:00:00Z"), json!("2018-12-31T23:59:59Z")),
+                (json!("2019-01-01T00:00:00Z"), json!("2019-01-27T23:59:59Z")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fetch_calendar_user_not_found() {
+        let server = MockServer::sequence(vec![Response::new(
+            200,
+            r#"{"data": {"user": null}, "errors": [{"type": "NOT_FOUND", "message": "Could not resolve to a User with the login of 'nobody'."}]}"#,
+        )]);
+        let client = GraphqlClient::new("secret").with_endpoint(server.url());
+
+        assert!(matches!(
+            client.fetch_calendar("nobody", None, None),
+            Err(ContriviewError::UserNotFound(u)) if u == "nobody"
+        ));
+    }
+
+    #[test]
+    fn test_fetch_calendar_unauthorized() {
+        let server = MockServer::sequence(vec![Response::new(
+            401,
+            r#"{"message": "Bad credentials"}"#,
+        )]);
+        let client = GraphqlClient::new("wrong").with_endpoint(server.url());
+
+        assert!(matches!(
+            client.fetch_calendar("k-nasa", None, None),
+            Err(ContriviewError::HttpStatus { status: 401, .. })
+        ));
+    }
+
+    #[test]
+    fn test_cache_id() {
+        let id = GraphqlClient::new("secret").cache_id().unwrap();
+
+        assert!(!id.contains("secret"));
+        assert_eq!(Some(id), GraphqlClient::new("secret").cache_id());
+        assert_ne!(
+            GraphqlClient::new("secret").cache_id(),
+            GraphqlClient::new("other").cache_id()
+        );
+    }
+
+    #[test]
+    fn test_calendar_from_response_errors() {
+        assert!(matches!(
+            calendar_from_response(r#"{"errors": [{"message": "rate limited"}]}"#, "k-nasa"),
+            Err(ContriviewError::Api(m)) if m == "rate limited"
+        ));
+        assert!(matches!(
+            calendar_from_response("<html>", "k-nasa"),
+            Err(ContriviewError::MarkupChanged(_))
+        ));
+    }
+}
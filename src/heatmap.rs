@@ -0,0 +1,282 @@
+//! The contribution calendar drawn as a grid of colored blocks in the terminal.
+
+use crate::calendar::ContributionCalendar;
+use crate::parser::PALETTE;
+use chrono::prelude::*;
+use std::fmt;
+
+/// Weeks shown, as many as on a profile page.
+pub const WEEKS: usize = 53;
+
+/// Width of the weekday labels left of the grid.
+const GUTTER: usize = 4;
+
+/// Blocks of increasing intensity, for terminals that cannot tell the greens apart.
+const SHADES: [char; 5] = ['·', '░', '▒', '▓', '█'];
+
+const BLOCK: char = '■';
+const RESET: &str = "\x1b[0m";
+
+/// Colors the terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    /// 24-bit colors, the palette of the calendar itself.
+    TrueColor,
+    /// The nearest colors of the 256-color palette.
+    Ansi256,
+    /// Shaded blocks in the basic green, for 16-color terminals.
+    Ansi16,
+    /// Shaded blocks without escape codes.
+    NoColor,
+}
+
+impl ColorMode {
+    /// Names accepted by [`str::parse`], `auto` excluded.
+    pub const NAMES: &'static [&'static str] = &["truecolor", "256", "16", "never"];
+
+    /// Guesses the colors of the terminal from `NO_COLOR`, `COLORTERM` and `TERM`.
+    pub fn detect() -> Self {
+        let var = |name| std::env::var(name).ok();
+        Self::from_env(
+            var("NO_COLOR").is_some_and(|v| !v.is_empty()),
+            var("COLORTERM").as_deref(),
+            var("TERM").as_deref(),
+        )
+    }
+
+    fn from_env(no_color: bool, colorterm: Option<&str>, term: Option<&str>) -> Self {
+        if no_color {
+            return ColorMode::NoColor;
+        }
+        if matches!(colorterm, Some("truecolor") | Some("24bit")) {
+            return ColorMode::TrueColor;
+        }
+
+        match term {
+            None | Some("") | Some("dumb") => ColorMode::NoColor,
+            Some(term) if term.contains("256color") => ColorMode::Ansi256,
+            Some(_) => ColorMode::Ansi16,
+        }
+    }
+
+    /// The cell of a day at intensity `level`, two columns wide.
+    fn cell(self, level: u8) -> String {
+        let level = usize::from(level.min(4));
+        let (r, g, b) = rgb(PALETTE[level]);
+
+        match self {
+            ColorMode::TrueColor => format!("\x1b[38;2;{};{};{}m{}{} ", r, g, b, BLOCK, RESET),
+            ColorMode::Ansi256 => format!("\x1b[38;5;{}m{}{} ", xterm_index(r, g, b), BLOCK, RESET),
+            ColorMode::Ansi16 => {
+                let color = if level == 0 { 90 } else { 32 };
+                format!("\x1b[{}m{}{} ", color, SHADES[level], RESET)
+            }
+            ColorMode::NoColor => format!("{} ", SHADES[level]),
+        }
+    }
+}
+
+impl std::str::FromStr for ColorMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "truecolor" => Ok(ColorMode::TrueColor),
+            "256" => Ok(ColorMode::Ansi256),
+            "16" => Ok(ColorMode::Ansi16),
+            "never" => Ok(ColorMode::NoColor),
+            _ => Err(format!("unknown color mode: {}", s)),
+        }
+    }
+}
+
+/// Components of a `#rrggbb` color.
+pub(crate) fn rgb(hex: &str) -> (u8, u8, u8) {
+    let component = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or_default();
+    (component(1), component(3), component(5))
+}
+
+/// Nearest color of the 256-color palette, from its 6×6×6 cube or its gray ramp.
+fn xterm_index(r: u8, g: u8, b: u8) -> u8 {
+    const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let nearest = |c: u8| {
+        (0..6)
+            .min_by_key(|&i| (i32::from(CUBE[i]) - i32::from(c)).abs())
+            .unwrap()
+    };
+    let distance = |(r2, g2, b2): (u8, u8, u8)| {
+        [(r, r2), (g, g2), (b, b2)]
+            .iter()
+            .map(|&(a, b)| (i32::from(a) - i32::from(b)).pow(2))
+            .sum::<i32>()
+    };
+
+    let (ri, gi, bi) = (nearest(r), nearest(g), nearest(b));
+    let cube = (CUBE[ri], CUBE[gi], CUBE[bi]);
+    let average = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+    let step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray = 8 + 10 * step;
+
+    if distance((gray, gray, gray)) < distance(cube) {
+        232 + step
+    } else {
+        (16 + 36 * ri + 6 * gi + bi) as u8
+    }
+}
+
+/// Sunday of the first of the [`WEEKS`] columns ending with the week of `to`.
+pub(crate) fn first_day(to: NaiveDate) -> NaiveDate {
+    let sunday = to - chrono::Duration::days(i64::from(to.weekday().num_days_from_sunday()));
+    sunday - chrono::Duration::weeks(WEEKS as i64 - 1)
+}
+
+/// The last [`WEEKS`] weeks of a calendar up to a date, one column per week from Sunday to
+/// Saturday, like the calendar of a profile page.
+#[derive(Debug, Clone, Copy)]
+pub struct Heatmap<'a> {
+    calendar: &'a ContributionCalendar,
+    to: NaiveDate,
+    colors: ColorMode,
+}
+
+impl<'a> Heatmap<'a> {
+    /// Draws the weeks of `calendar` ending with the one containing `to`, in the colors of the
+    /// terminal.
+    pub fn new(calendar: &'a ContributionCalendar, to: NaiveDate) -> Self {
+        Heatmap {
+            calendar,
+            to,
+            colors: ColorMode::detect(),
+        }
+    }
+
+    /// Draws with `colors` instead of the detected ones.
+    pub fn with_colors(mut self, colors: ColorMode) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    fn start(&self) -> NaiveDate {
+        first_day(self.to)
+    }
+
+    /// Abbreviated month names over the first column of each month.
+    pub(crate) fn month_labels(&self) -> String {
+        let start = self.start();
+        let mut line = " ".repeat(GUTTER);
+        let mut previous = None;
+
+        for week in 0..WEEKS {
+            let sunday = start + chrono::Duration::weeks(week as i64);
+            let column = GUTTER + week * 2;
+            if previous != Some(sunday.month()) && line.chars().count() <= column {
+                line.push_str(&" ".repeat(column - line.chars().count()));
+                line.push_str(&sunday.format("%b").to_string());
+            }
+            previous = Some(sunday.month());
+        }
+
+        line
+    }
+}
+
+impl fmt::Display for Heatmap<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.month_labels())?;
+
+        let start = self.start();
+        for weekday in 0..7 {
+            let label = match weekday {
+                1 => "Mon",
+                3 => "Wed",
+                5 => "Fri",
+                _ => "",
+            };
+            let mut line = format!("{:<width$}", label, width = GUTTER);
+
+            for week in 0..WEEKS {
+                let date = start + chrono::Duration::days((week * 7 + weekday) as i64);
+                match self.calendar.get(date).filter(|_| date <= self.to) {
+                    Some(day) => line.push_str(&self.colors.cell(day.level)),
+                    None => line.push_str("  "),
+                }
+            }
+            writeln!(f, "{}", line.trim_end())?;
+        }
+
+        let legend: String = (0..5).map(|level| self.colors.cell(level)).collect();
+        write!(f, "\n{}Less {}More", " ".repeat(GUTTER), legend)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{date, sample_calendar};
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(
+            ColorMode::NoColor,
+            ColorMode::from_env(true, Some("truecolor"), Some("xterm"))
+        );
+        assert_eq!(
+            ColorMode::TrueColor,
+            ColorMode::from_env(false, Some("24bit"), Some("xterm"))
+        );
+        assert_eq!(
+            ColorMode::Ansi256,
+            ColorMode::from_env(false, None, Some("xterm-256color"))
+        );
+        assert_eq!(
+            ColorMode::Ansi16,
+            ColorMode::from_env(false, None, Some("xterm"))
+        );
+        assert_eq!(
+            ColorMode::NoColor,
+            ColorMode::from_env(false, None, Some("dumb"))
+        );
+        assert_eq!(ColorMode::NoColor, ColorMode::from_env(false, None, None));
+    }
+
+    #[test]
+    fn test_cells() {
+        assert_eq!("\x1b[38;2;25;97;39m■\x1b[0m ", ColorMode::TrueColor.cell(4));
+        assert_eq!("\x1b[38;5;22m■\x1b[0m ", ColorMode::Ansi256.cell(4));
+        assert_eq!("\x1b[38;5;255m■\x1b[0m ", ColorMode::Ansi256.cell(0));
+        assert_eq!("\x1b[32m▒\x1b[0m ", ColorMode::Ansi16.cell(2));
+        assert_eq!("· ", ColorMode::NoColor.cell(0));
+    }
+
+    #[test]
+    fn test_grid() {
+        let calendar = sample_calendar();
+        let heatmap = Heatmap::new(&calendar, date("2019-01-26")).with_colors(ColorMode::NoColor);
+        let text = heatmap.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(10, lines.len());
+        assert!(lines[0].starts_with("    Jan Feb     Mar"));
+        assert!(lines[2].starts_with("Mon "));
+        // 2019-01-26 is a Saturday, so the grid ends on the last row.
+        assert_eq!(GUTTER + WEEKS * 2 - 1, lines[7].chars().count());
+        assert_eq!("    Less · ░ ▒ ▓ █ More", lines[9]);
+
+        let cells = |line: &str| line.chars().skip(GUTTER).step_by(2).collect::<String>();
+        assert_eq!(WEEKS, cells(lines[7]).chars().count());
+        assert!(cells(lines[7]).ends_with('░'));
+    }
+
+    #[test]
+    fn test_days_outside_calendar_are_blank() {
+        let calendar = sample_calendar();
+        let text = Heatmap::new(&calendar, date("2019-01-23"))
+            .with_colors(ColorMode::NoColor)
+            .to_string();
+        let lines: Vec<&str> = text.lines().collect();
+
+        // Thursday 2019-01-24 onwards is after the target date.
+        assert_eq!(GUTTER + (WEEKS - 1) * 2 - 1, lines[5].chars().count());
+        assert_eq!(GUTTER + WEEKS * 2 - 1, lines[4].chars().count());
+    }
+}
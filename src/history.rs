@@ -0,0 +1,356 @@
+//! Local history of contributions, kept beyond the year the forges show.
+//!
+//! `contriview sync` appends every fetched day to a SQLite database, so totals keep growing
+//! after the days fall out of the rolling year.
+
+use crate::calendar::{ContributionCalendar, ContributionDay, DateRange};
+use crate::error::{ContriviewError, Result};
+use crate::source::ContributionSource;
+use chrono::prelude::*;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const SCHEMA: &str = "CREATE TABLE IF NOT EXISTS days (
+    source TEXT NOT NULL,
+    username TEXT NOT NULL,
+    date TEXT NOT NULL,
+    count INTEGER NOT NULL,
+    level INTEGER NOT NULL,
+    PRIMARY KEY (source, username, date)
+)";
+
+/// The history database.
+///
+/// Days are stored per source and user, so that the same name on two forges stays apart.
+#[derive(Debug)]
+pub struct History {
+    conn: Connection,
+}
+
+impl History {
+    /// Opens the database at `path`, creating it if needed.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|source| ContriviewError::Io {
+                path: dir.to_path_buf(),
+                source,
+            })?;
+        }
+
+        Self::init(Connection::open(path).map_err(db_error)?)
+    }
+
+    /// A database that only lives as long as the returned value.
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory().map_err(db_error)?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute(SCHEMA, []).map_err(db_error)?;
+        Ok(History { conn })
+    }
+
+    /// `contriview/history.sqlite3` under the platform data directory.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("contriview").join("history.sqlite3"))
+    }
+
+    /// Stores the days of `calendar`. Days already stored are overwritten with the new counts,
+    /// so syncing again during the day keeps today up to date. Returns the number of days written.
+    pub fn append(
+        &mut self,
+        source: &str,
+        username: &str,
+        calendar: &ContributionCalendar,
+    ) -> Result<usize> {
+        let tx = self.conn.transaction().map_err(db_error)?;
+        {
+            let mut insert = tx
+                .prepare(
+                    "INSERT INTO days (source, username, date, count, level)
+                     VALUES (?1, ?2, ?3, ?4, ?5)
+                     ON CONFLICT (source, username, date)
+                     DO UPDATE SET count = excluded.count, level = excluded.level",
+                )
+                .map_err(db_error)?;
+            for day in calendar.days() {
+                insert
+                    .execute(params![source, username, day.date, day.count, day.level])
+                    .map_err(db_error)?;
+            }
+        }
+        tx.commit().map_err(db_error)?;
+
+        Ok(calendar.days().len())
+    }
+
+    /// Returns the stored days of `username` within `range`.
+    pub fn calendar(
+        &self,
+        source: &str,
+        username: &str,
+        range: &DateRange,
+    ) -> Result<ContributionCalendar> {
+        let mut select = self
+            .conn
+            .prepare(
+                "SELECT date, count, level FROM days
+                 WHERE source = ?1 AND username = ?2
+                   AND (?3 IS NULL OR date >= ?3) AND (?4 IS NULL OR date <= ?4)
+                 ORDER BY date",
+            )
+            .map_err(db_error)?;
+
+        let days = select
+            .query_map(params![source, username, range.from, range.to], |row| {
+                Ok(ContributionDay {
+                    date: row.get(0)?,
+                    count: row.get(1)?,
+                    level: row.get(2)?,
+                })
+            })
+            .map_err(db_error)?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(db_error)?;
+
+        Ok(ContributionCalendar::new(days))
+    }
+}
+
+fn db_error(e: rusqlite::Error) -> ContriviewError {
+    ContriviewError::History(e.to_string())
+}
+
+/// Reads calendars from the [`History`] instead of fetching them.
+#[derive(Debug)]
+pub struct HistorySource {
+    history: Mutex<History>,
+    source: String,
+}
+
+impl HistorySource {
+    /// Reads the days synced from `source`.
+    pub fn new(history: History, source: &str) -> Self {
+        HistorySource {
+            history: Mutex::new(history),
+            source: source.to_string(),
+        }
+    }
+}
+
+impl ContributionSource for HistorySource {
+    fn fetch(&self, username: &str, range: &DateRange) -> Result<ContributionCalendar> {
+        let calendar = self
+            .history
+            .lock()
+            .unwrap()
+            .calendar(&self.source, username, range)?;
+
+        if calendar.is_empty() {
+            return Err(ContriviewError::History(format!(
+                "no history of {}, run contriview sync first",
+                username
+            )));
+        }
+
+        Ok(calendar)
+    }
+}
+
+/// Contributions of one calendar year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct YearTotal {
+    /// Calendar year.
+    pub year: i32,
+    /// Contributions made during `year`.
+    pub contributions: u64,
+}
+
+/// Per-year and all-time totals of a stored history.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HistorySummary {
+    username: String,
+    first_day: Option<NaiveDate>,
+    last_day: Option<NaiveDate>,
+    total: u64,
+    years: Vec<YearTotal>,
+}
+
+impl HistorySummary {
+    /// Totals `calendar` by year.
+    pub fn new(username: &str, calendar: &ContributionCalendar) -> Self {
+        let mut years: BTreeMap<i32, u64> = BTreeMap::new();
+        for day in calendar.days() {
+            *years.entry(day.date.year()).or_insert(0) += u64::from(day.count);
+        }
+
+        HistorySummary {
+            username: username.to_string(),
+            first_day: calendar.days().first().map(|d| d.date),
+            last_day: calendar.days().last().map(|d| d.date),
+            total: years.values().sum(),
+            years: years
+                .into_iter()
+                .map(|(year, contributions)| YearTotal {
+                    year,
+                    contributions,
+                })
+                .collect(),
+        }
+    }
+
+    /// Name of the account.
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    /// Oldest stored day.
+    pub fn first_day(&self) -> Option<NaiveDate> {
+        self.first_day
+    }
+
+    /// Latest stored day.
+    pub fn last_day(&self) -> Option<NaiveDate> {
+        self.last_day
+    }
+
+    /// Totals of every year with stored days, oldest first.
+    pub fn years(&self) -> &[YearTotal] {
+        &self.years
+    }
+
+    /// All-time total.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+}
+
+impl fmt::Display for HistorySummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.first_day, self.last_day) {
+            (Some(first), Some(last)) => {
+                writeln!(f, "history: {} ({}..{})", self.username, first, last)?
+            }
+            _ => writeln!(f, "history: {}", self.username)?,
+        }
+        for year in &self.years {
+            writeln!(f, "{}: {}", year.year, year.contributions)?;
+        }
+        write!(f, "total: {}", self.total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{date, sample_calendar};
+
+    fn day(date_str: &str, count: u32) -> ContributionDay {
+        ContributionDay {
+            date: date(date_str),
+            count,
+            level: 1,
+        }
+    }
+
+    #[test]
+    fn test_append_and_read_back() {
+        let mut history = History::open_in_memory().unwrap();
+        let calendar = sample_calendar();
+
+        assert_eq!(371, history.append("github", "k-nasa", &calendar).unwrap());
+        assert_eq!(
+            calendar,
+            history
+                .calendar("github", "k-nasa", &DateRange::default())
+                .unwrap()
+        );
+        assert!(history
+            .calendar("gitlab", "k-nasa", &DateRange::default())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_append_keeps_old_days_and_updates_counts() {
+        let mut history = History::open_in_memory().unwrap();
+        let old = ContributionCalendar::new(vec![day("2017-12-31", 4), day("2018-01-01", 1)]);
+        let new = ContributionCalendar::new(vec![day("2018-01-01", 3), day("2018-01-02", 2)]);
+
+        history.append("github", "k-nasa", &old).unwrap();
+        history.append("github", "k-nasa", &new).unwrap();
+
+        assert_eq!(
+            history
+                .calendar("github", "k-nasa", &DateRange::default())
+                .unwrap()
+                .days(),
+            &[
+                day("2017-12-31", 4),
+                day("2018-01-01", 3),
+                day("2018-01-02", 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_calendar_within_range() {
+        let mut history = History::open_in_memory().unwrap();
+        history
+            .append("github", "k-nasa", &sample_calendar())
+            .unwrap();
+
+        let range = DateRange::new(date("2018-12-01"), date("2018-12-31"));
+        let calendar = history.calendar("github", "k-nasa", &range).unwrap();
+
+        assert_eq!(31, calendar.days().len());
+        assert_eq!(sample_calendar().within(&range), calendar);
+    }
+
+    #[test]
+    fn test_history_source() {
+        let mut history = History::open_in_memory().unwrap();
+        history
+            .append("github", "k-nasa", &sample_calendar())
+            .unwrap();
+        let source = HistorySource::new(history, "github");
+
+        assert_eq!(
+            sample_calendar(),
+            source.fetch("k-nasa", &DateRange::default()).unwrap()
+        );
+        assert!(matches!(
+            source.fetch("nobody", &DateRange::default()),
+            Err(ContriviewError::History(_))
+        ));
+    }
+
+    #[test]
+    fn test_summary() {
+        let summary = HistorySummary::new("k-nasa", &sample_calendar());
+
+        assert_eq!(
+            summary.years(),
+            &[
+                YearTotal {
+                    year: 2018,
+                    contributions: 3272
+                },
+                YearTotal {
+                    year: 2019,
+                    contributions: 260
+                },
+            ]
+        );
+        assert_eq!(3532, summary.total());
+        assert_eq!(
+            "history: k-nasa (2018-01-21..2019-01-26)\n2018: 3272\n2019: 260\ntotal: 3532",
+            summary.to_string()
+        );
+    }
+}
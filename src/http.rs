@@ -0,0 +1,323 @@
+//! A blocking HTTP client with per-host rate limiting and retries.
+
+use crate::error::{ContriviewError, Result};
+use chrono::prelude::*;
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::header::RETRY_AFTER;
+use reqwest::StatusCode;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Longest `Retry-After` waited for, so that one header cannot stall the program.
+pub const MAX_RETRY_AFTER: Duration = Duration::from_secs(300);
+
+/// Slowest rate limit, one request every 1000 seconds. Lower positive rates are raised to it.
+pub const MIN_RATE_LIMIT: f64 = 0.001;
+
+/// When and how often a request answered with 429 or 5xx is retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for every following one.
+    pub initial_backoff: Duration,
+    /// Upper bound of the exponential backoff. `Retry-After` is honored even above it, up to
+    /// [`MAX_RETRY_AFTER`].
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retries.
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Backoff before retry number `attempt`, starting at 0.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt);
+        self.initial_backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |d| d.min(self.max_backoff))
+    }
+}
+
+/// Spaces out requests to the same host.
+#[derive(Debug, Default)]
+struct RateLimiter {
+    interval: Duration,
+    next: Mutex<HashMap<String, Instant>>,
+}
+
+impl RateLimiter {
+    /// Blocks until a request to `host` is allowed.
+    fn acquire(&self, host: &str) {
+        if self.interval.is_zero() {
+            return;
+        }
+
+        let wait = {
+            let mut next = self.next.lock().unwrap();
+            let now = Instant::now();
+            let slot = next.get(host).copied().unwrap_or(now).max(now);
+            next.insert(host.to_string(), slot + self.interval);
+            slot - now
+        };
+
+        thread::sleep(wait);
+    }
+}
+
+/// The HTTP client shared by every network source.
+///
+/// Clones share the same connection pool and rate limits.
+#[derive(Debug, Clone, Default)]
+pub struct HttpClient {
+    client: Client,
+    retry: RetryPolicy,
+    limiter: Arc<RateLimiter>,
+}
+
+impl HttpClient {
+    /// A client with the default [`RetryPolicy`] and no rate limit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses `retry` for 429 and 5xx responses.
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Sends at most `per_second` requests per second to each host. 0, or anything but a finite
+    /// positive number, disables the limit.
+    pub fn with_rate_limit(mut self, per_second: f64) -> Self {
+        let interval = if per_second.is_finite() && per_second > 0.0 {
+            Duration::try_from_secs_f64(1.0 / per_second.max(MIN_RATE_LIMIT))
+                .unwrap_or(Duration::ZERO)
+        } else {
+            Duration::ZERO
+        };
+        self.limiter = Arc::new(RateLimiter {
+            interval,
+            next: Mutex::new(HashMap::new()),
+        });
+        self
+    }
+
+    /// GETs `url`.
+    pub fn get(&self, url: &str) -> Result<Response> {
+        self.execute(url, |client| client.get(url))
+    }
+
+    /// Sends the request built by `build` to `url`, waiting for the rate limiter and retrying
+    /// as the [`RetryPolicy`] allows. The last response is returned whatever its status.
+    pub fn execute<F>(&self, url: &str, build: F) -> Result<Response>
+    where
+        F: Fn(&Client) -> RequestBuilder,
+    {
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|u| {
+                let port = u.port_or_known_default().unwrap_or_default();
+                u.host_str().map(|h| format!("{}:{}", h, port))
+            })
+            .unwrap_or_default();
+
+        let mut attempt = 0;
+        loop {
+            self.limiter.acquire(&host);
+
+            let resp = build(&self.client)
+                .send()
+                .map_err(|source| ContriviewError::Network {
+                    url: url.to_string(),
+                    source,
+                })?;
+
+            let status = resp.status();
+            let retryable = status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error();
+            if !retryable || attempt >= self.retry.max_retries {
+                return Ok(resp);
+            }
+
+            let delay = retry_after(&resp).unwrap_or_else(|| self.retry.backoff(attempt));
+            thread::sleep(delay);
+            attempt += 1;
+        }
+    }
+}
+
+/// Reads `Retry-After` as either delay seconds or an HTTP date.
+fn retry_after(resp: &Response) -> Option<Duration> {
+    let value = resp.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    parse_retry_after(value, Utc::now())
+}
+
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let delay = match value.trim().parse::<u64>() {
+        Ok(seconds) => Duration::from_secs(seconds),
+        Err(_) => {
+            let date = DateTime::parse_from_rfc2822(value.trim()).ok()?;
+            (date.with_timezone(&Utc) - now)
+                .to_std()
+                .unwrap_or_default()
+        }
+    };
+
+    Some(delay.min(MAX_RETRY_AFTER))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockServer, Response as MockResponse};
+
+    fn fast_retry(max_retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_retries,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(10),
+        }
+    }
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+        };
+
+        assert_eq!(Duration::from_millis(100), policy.backoff(0));
+        assert_eq!(Duration::from_millis(200), policy.backoff(1));
+        assert_eq!(Duration::from_millis(400), policy.backoff(2));
+        assert_eq!(Duration::from_millis(500), policy.backoff(3));
+        assert_eq!(Duration::from_millis(500), policy.backoff(40));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = Utc.with_ymd_and_hms(2015, 10, 21, 7, 28, 0).unwrap();
+
+        assert_eq!(
+            Some(Duration::from_secs(120)),
+            parse_retry_after("120", now)
+        );
+        assert_eq!(
+            Some(Duration::from_secs(30)),
+            parse_retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now)
+        );
+        assert_eq!(
+            Some(Duration::ZERO),
+            parse_retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now)
+        );
+        assert_eq!(None, parse_retry_after("soon", now));
+        assert_eq!(
+            Some(MAX_RETRY_AFTER),
+            parse_retry_after("18446744073709551615", now)
+        );
+        assert_eq!(
+            Some(MAX_RETRY_AFTER),
+            parse_retry_after("Fri, 01 Jan 2100 00:00:00 GMT", now)
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_intervals() {
+        let interval = |per_second| {
+            HttpClient::new()
+                .with_rate_limit(per_second)
+                .limiter
+                .interval
+        };
+
+        assert_eq!(Duration::from_millis(500), interval(2.0));
+        assert_eq!(Duration::from_secs(1000), interval(1e-30));
+        for disabled in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert_eq!(Duration::ZERO, interval(disabled));
+        }
+    }
+
+    #[test]
+    fn test_retries_server_errors() {
+        let server = MockServer::sequence(vec![
+            MockResponse::new(503, ""),
+            MockResponse::new(500, ""),
+            MockResponse::new(200, "ok"),
+        ]);
+        let http = HttpClient::new().with_retry(fast_retry(3));
+
+        let resp = http.get(server.url()).unwrap();
+
+        assert_eq!(StatusCode::OK, resp.status());
+        assert_eq!(3, server.requests().len());
+    }
+
+    #[test]
+    fn test_gives_up_after_max_retries() {
+        let server = MockServer::sequence(vec![MockResponse::new(429, "")]);
+        let http = HttpClient::new().with_retry(fast_retry(2));
+
+        let resp = http.get(server.url()).unwrap();
+
+        assert_eq!(StatusCode::TOO_MANY_REQUESTS, resp.status());
+        assert_eq!(3, server.requests().len());
+    }
+
+    #[test]
+    fn test_does_not_retry_client_errors() {
+        let server = MockServer::sequence(vec![MockResponse::new(404, "")]);
+        let http = HttpClient::new().with_retry(fast_retry(3));
+
+        assert_eq!(
+            StatusCode::NOT_FOUND,
+            http.get(server.url()).unwrap().status()
+        );
+        assert_eq!(1, server.requests().len());
+    }
+
+    #[test]
+    fn test_honors_retry_after() {
+        let server = MockServer::sequence(vec![
+            MockResponse::new(429, "").header("Retry-After", "1"),
+            MockResponse::new(200, "ok"),
+        ]);
+        let http = HttpClient::new().with_retry(fast_retry(1));
+
+        let started = Instant::now();
+        let resp = http.get(server.url()).unwrap();
+
+        assert_eq!(StatusCode::OK, resp.status());
+        assert!(started.elapsed() >= Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let server = MockServer::sequence(vec![MockResponse::new(200, "ok")]);
+        let http = HttpClient::new().with_rate_limit(20.0);
+
+        let started = Instant::now();
+        for _ in 0..4 {
+            http.get(server.url()).unwrap();
+        }
+
+        // The first request is immediate, the next three wait 50ms each.
+        assert!(started.elapsed() >= Duration::from_millis(150));
+    }
+}
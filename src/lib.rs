@@ -0,0 +1,56 @@
+//! Summaries of GitHub contribution calendars.
+//!
+//! ```no_run
+//! use chrono::Local;
+//! use contriview::{fetch_html, ContriView};
+//!
+//! let html = fetch_html("k-nasa").unwrap();
+//! let view = ContriView::from_html(&html, Local::now().date_naive()).unwrap();
+//!
+//! println!("{}", view);
+//! ```
+
+#![warn(missing_docs)]
+
+pub mod cache;
+pub mod calendar;
+pub mod compare;
+pub mod config;
+mod contriview;
+pub mod engine;
+pub mod error;
+pub mod export;
+pub mod fetch;
+pub mod format;
+pub mod git;
+pub mod gitea;
+pub mod gitlab;
+pub mod graphql;
+pub mod heatmap;
+pub mod history;
+pub mod http;
+pub mod parser;
+pub mod period;
+pub mod source;
+pub mod stats;
+pub mod streak;
+pub mod team;
+pub mod trend;
+pub mod tui;
+pub mod week;
+
+#[cfg(test)]
+mod fixtures;
+#[cfg(test)]
+mod mock_server;
+
+pub use crate::calendar::{ContributionCalendar, ContributionDay, DateRange};
+pub use crate::compare::Comparison;
+pub use crate::config::Config;
+pub use crate::contriview::ContriView;
+pub use crate::error::{ContriviewError, Result};
+pub use crate::fetch::fetch_html;
+pub use crate::format::{OutputFormat, Report};
+pub use crate::source::ContributionSource;
+pub use crate::team::TeamView;
+pub use crate::week::WeekMode;
@@ -1,56 +1,932 @@
-mod contriview;
-
-use crate::contriview::ContriView;
 use chrono::*;
 use clap::*;
-use reqwest::blocking::Client;
+use contriview::cache::{Cache, CacheMode, CachedSource};
+use contriview::compare::USER_COLUMN;
+use contriview::config::{SOURCES, WEEK_MODES};
+use contriview::engine::FetchEngine;
+use contriview::error::USAGE_EXIT_CODE;
+use contriview::export::{Export, Theme};
+use contriview::fetch::GithubHtmlSource;
+use contriview::format::{
+    render, render_comparison, render_history, render_periods, render_team, render_trends,
+};
+use contriview::git::GitSource;
+use contriview::gitea::GiteaSource;
+use contriview::gitlab::GitlabSource;
+use contriview::graphql::GraphqlClient;
+use contriview::heatmap::{ColorMode, Heatmap};
+use contriview::history::{History, HistorySource, HistorySummary};
+use contriview::http::{HttpClient, RetryPolicy, MIN_RATE_LIMIT};
+use contriview::period::{covering_range, Period, PeriodTable};
+use contriview::source::{FileSource, RecordedSource};
+use contriview::trend::{Granularity, Trend};
+use contriview::tui;
+use contriview::{
+    Comparison, Config, ContriView, ContributionSource, ContriviewError, DateRange, OutputFormat,
+    Report, Result, TeamView, WeekMode,
+};
+use std::io::IsTerminal;
 
 fn main() {
-    let matches = app().get_matches();
+    if let Err(e) = run() {
+        eprintln!("error: {}", e);
+        std::process::exit(e.exit_code());
+    }
+}
+
+fn run() -> Result<()> {
+    let matches = app().get_matches_safe().unwrap_or_else(|e| exit_usage(e));
 
-    let username = match matches.value_of("username") {
-        None => {
-            app().print_help().unwrap();
-            return;
+    let settings = settings(&matches)?;
+    let format: OutputFormat = settings.format.as_deref().unwrap_or("text").parse()?;
+    let range = match matches.value_of("year") {
+        Some(_) => {
+            let year = value_t!(matches, "year", i32).unwrap_or_else(|e| exit_usage(e));
+            DateRange::year(year)
+                .ok_or_else(|| ContriviewError::InvalidDate(format!("{}-01-01", year)))?
         }
-        Some(u) => u,
+        None => DateRange {
+            from: matches.value_of("from").map(date_from_string).transpose()?,
+            to: matches.value_of("to").map(date_from_string).transpose()?,
+        },
     };
 
-    let url = format!("https://github.com/users/{}/contributions", username);
-
-    let client = Client::new();
-    let resp = match client.get(&url).send() {
-        Ok(res) => res,
-        Err(_) => {
-            println!("Failed fetch from {}", url);
-            return;
+    let today = settings.today();
+    let date = match matches.value_of("date") {
+        Some(d) => date_from_string(d)?,
+        None => range.to.map_or(today, |to| to.min(today)),
+    };
+    // Stats relative to a past date need the year ending on that date, not the latest one.
+    let range = if range.is_unbounded() && date < today {
+        DateRange {
+            from: None,
+            to: Some(date),
         }
+    } else {
+        range
     };
 
-    let html = resp.text().unwrap();
+    let week = week_mode(&matches, &settings, date)?;
+    let fiscal_start = value_t!(matches, "fiscal-start", u32).unwrap_or_else(|e| exit_usage(e));
+    let periods = matches
+        .values_of("period")
+        .into_iter()
+        .flatten()
+        .map(|spec| Period::parse(spec, fiscal_start))
+        .collect::<Result<Vec<_>>>()?;
+    let engine =
+        FetchEngine::new(value_t!(matches, "concurrency", usize).unwrap_or_else(|e| exit_usage(e)));
 
-    let date = match matches.value_of("date") {
-        Some(d) => date_from_string(d),
-        None => Local::today(),
+    match matches.subcommand() {
+        ("sync", Some(sub)) => {
+            let usernames: Vec<String> = sub
+                .values_of("username")
+                .unwrap()
+                .map(String::from)
+                .collect();
+            let source = source(&matches, &settings)?;
+            let mut history = open_history(&matches)?;
+            let label = history_source(&matches, &settings);
+
+            for (username, calendar) in usernames
+                .iter()
+                .zip(engine.fetch_all(&*source, &usernames, &range))
+            {
+                let days = history.append(&label, username, &calendar?)?;
+                println!("{}: {} days synced", username, days);
+            }
+            return Ok(());
+        }
+        ("export", Some(sub)) => {
+            let username = sub.value_of("username").unwrap();
+            let theme = sub
+                .value_of("theme")
+                .or(settings.theme.as_deref())
+                .unwrap_or("light");
+            let mut theme = Theme::named(theme).unwrap();
+            if let Some(colors) = sub.value_of("colors") {
+                theme = theme.with_scale(colors)?;
+            }
+            let calendar = source(&matches, &settings)?.fetch(username, &range)?;
+            let (from, to) = range.or_last_year(date);
+            let view = ContriView::from_calendar_with_week(&calendar, to, week);
+
+            let mut export = Export::new(&calendar, from, to)
+                .with_theme(theme)
+                .with_title(sub.value_of("title").unwrap_or(username));
+            if !sub.is_present("no-summary") {
+                export = export.with_summary(&view);
+            }
+
+            if let Some(path) = sub.value_of("svg") {
+                write_file(path, export.to_svg().as_bytes())?;
+            }
+            if let Some(path) = sub.value_of("png") {
+                write_file(path, &export.to_png()?)?;
+            }
+            return Ok(());
+        }
+        ("tui", Some(sub)) => {
+            let mut usernames: Vec<String> = sub
+                .values_of("username")
+                .into_iter()
+                .flatten()
+                .map(String::from)
+                .collect();
+            if usernames.is_empty() {
+                usernames = settings.users();
+            }
+            if usernames.is_empty() && ignores_username(&matches, &settings) {
+                usernames.push(String::new());
+            }
+
+            let source = source(&matches, &settings)?;
+            let range = match range.from {
+                None => covering_range(&periods, date)?.unwrap_or(range),
+                Some(_) => range,
+            };
+            let mut users = vec![];
+            for (username, calendar) in usernames
+                .iter()
+                .zip(engine.fetch_all(&*source, &usernames, &range))
+            {
+                users.push((username.clone(), calendar?));
+            }
+
+            return tui::run(
+                tui::App::new(users, date)
+                    .with_week(week)
+                    .with_periods(periods),
+            );
+        }
+        ("config", Some(_)) => {
+            // `show` is the only subcommand.
+            let path = match matches.value_of("config") {
+                Some(path) => Some(path.into()),
+                None => Config::default_path(),
+            };
+            if let Some(path) = path {
+                println!("# {}", path.display());
+            }
+            let toml = toml::to_string(&settings.redacted())
+                .map_err(|e| ContriviewError::Output(e.to_string()))?;
+            print!("{}", toml);
+            return Ok(());
+        }
+        ("history", Some(sub)) => {
+            let username = sub.value_of("username").unwrap();
+            let source = HistorySource::new(
+                open_history(&matches)?,
+                &history_source(&matches, &settings),
+            );
+            let summary = HistorySummary::new(username, &source.fetch(username, &range)?);
+
+            println!("{}", render_history(&summary, format)?);
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let mut usernames: Vec<String> = matches
+        .values_of("username")
+        .into_iter()
+        .flatten()
+        .map(String::from)
+        .collect();
+    if let Some(path) = matches.value_of("users-file") {
+        usernames.extend(users_from_file(path)?);
+    }
+    if usernames.is_empty() && !matches.is_present("team") {
+        if let Some(user) = &settings.user {
+            usernames.push(user.clone());
+        } else if !ignores_username(&matches, &settings) {
+            return Err(ContriviewError::Config(
+                "no username given and no default user configured".to_string(),
+            ));
+        }
+    }
+
+    let source: Box<dyn ContributionSource> = if matches.is_present("history") {
+        Box::new(HistorySource::new(
+            open_history(&matches)?,
+            &history_source(&matches, &settings),
+        ))
+    } else {
+        source(&matches, &settings)?
     };
 
-    println!("{}", ContriView::from_html(&html, date).unwrap())
+    if let Some(name) = matches.value_of("team") {
+        let usernames = settings.team(name)?;
+        let mut members = vec![];
+        for (username, calendar) in usernames
+            .iter()
+            .zip(engine.fetch_all(&*source, usernames, &range))
+        {
+            members.push((username.clone(), calendar?));
+        }
+
+        let team = TeamView::with_week(name, &members, date, week);
+        println!("{}", render_team(&team, format)?);
+        return Ok(());
+    }
+
+    if !periods.is_empty() {
+        // Every period is summed from the same calendar, so fetch one range covering them all.
+        let range = match range.from {
+            None => covering_range(&periods, date)?.unwrap_or(range),
+            Some(_) => range,
+        };
+        if usernames.is_empty() {
+            usernames.push(String::new());
+        }
+        let calendars = engine.fetch_all(&*source, &usernames, &range);
+        let mut rows = vec![];
+        for (username, calendar) in usernames.into_iter().zip(calendars) {
+            rows.push((username, calendar?));
+        }
+
+        let table = PeriodTable::new(
+            rows.iter()
+                .map(|(username, calendar)| (username.clone(), calendar)),
+            &periods,
+            date,
+        )?;
+        println!("{}", render_periods(&table, format)?);
+        return Ok(());
+    }
+
+    if matches.is_present("graph") {
+        if usernames.is_empty() {
+            usernames.push(String::new());
+        }
+        let colors = color_mode(&matches);
+        let calendars = engine.fetch_all(&*source, &usernames, &range);
+        let single = usernames.len() == 1;
+        for (i, (username, calendar)) in usernames.iter().zip(calendars).enumerate() {
+            if i > 0 {
+                println!();
+            }
+            if !single {
+                println!("{}", username);
+            }
+            println!("{}", Heatmap::new(&calendar?, date).with_colors(colors));
+        }
+        return Ok(());
+    }
+
+    if let Some(granularity) = matches.value_of("trend") {
+        let granularity = match granularity {
+            "daily" => Granularity::Daily,
+            "weekly" => Granularity::Weekly,
+            _ => Granularity::Monthly,
+        };
+        if usernames.is_empty() {
+            usernames.push(String::new());
+        }
+        let (from, to) = range.or_last_year(date);
+        let calendars = engine.fetch_all(&*source, &usernames, &range);
+        let mut trends = vec![];
+        for (username, calendar) in usernames.iter().zip(calendars) {
+            trends.push(Trend::new(username, &calendar?, granularity, from, to));
+        }
+
+        println!("{}", render_trends(&trends, format)?);
+        return Ok(());
+    }
+
+    if usernames.len() > 1 {
+        let calendars = engine.fetch_all(&*source, &usernames, &range);
+        let mut rows = vec![];
+        for (username, calendar) in usernames.into_iter().zip(calendars) {
+            rows.push((
+                username,
+                ContriView::from_calendar_with_week(&calendar?, date, week),
+            ));
+        }
+
+        let mut comparison = Comparison::new(rows);
+        if let Some(column) = matches.value_of("sort") {
+            comparison.sort_by(column, matches.is_present("reverse"))?;
+        }
+
+        println!("{}", render_comparison(&comparison, format)?);
+        return Ok(());
+    }
+
+    let username = usernames.first().map(String::as_str).unwrap_or_default();
+    let calendar = source.fetch(username, &range)?;
+    let view = ContriView::from_calendar_with_week(&calendar, date, week);
+
+    println!("{}", render(&Report::new(&view, &calendar), format)?);
+
+    Ok(())
+}
+
+/// Prints a clap error and exits with the code of usage errors, or with success for `--help` and
+/// `--version`.
+fn exit_usage(e: clap::Error) -> ! {
+    if !e.use_stderr() {
+        e.exit();
+    }
+    eprintln!("{}", e.message);
+    std::process::exit(USAGE_EXIT_CODE);
 }
 
 fn app() -> App<'static, 'static> {
     App::new(crate_name!())
         .version(crate_version!())
-        .arg(Arg::with_name("username").required(true))
+        .setting(AppSettings::SubcommandsNegateReqs)
+        .subcommand(
+            SubCommand::with_name("sync")
+                .about("appends the fetched days of users to the local history")
+                .arg(Arg::with_name("username").multiple(true).required(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("export")
+                .about("draws the calendar of a user as an SVG or PNG image")
+                .arg(Arg::with_name("username").required(true))
+                .arg(
+                    Arg::with_name("svg")
+                        .help("writes the image as SVG")
+                        .value_name("path")
+                        .long("svg")
+                        .required_unless("png"),
+                )
+                .arg(
+                    Arg::with_name("png")
+                        .help("writes the image as PNG")
+                        .value_name("path")
+                        .long("png"),
+                )
+                .arg(
+                    Arg::with_name("theme")
+                        .help("colors of the image, defaults to the configured theme or light")
+                        .value_name("theme")
+                        .long("theme")
+                        .possible_values(Theme::NAMES),
+                )
+                .arg(
+                    Arg::with_name("colors")
+                        .help("five comma-separated #rrggbb colors, from no contributions to the most")
+                        .value_name("colors")
+                        .long("colors"),
+                )
+                .arg(
+                    Arg::with_name("title")
+                        .help("text above the calendar, defaults to the username")
+                        .value_name("text")
+                        .long("title"),
+                )
+                .arg(
+                    Arg::with_name("no-summary")
+                        .help("leaves the totals out of the image")
+                        .long("no-summary"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("tui")
+                .about("browses the calendars of users, or of the configured teams, interactively")
+                .arg(Arg::with_name("username").multiple(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("config")
+                .about("prints the settings read from the configuration file")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("show")
+                        .about("prints the settings, the token redacted"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("history")
+                .about("prints per-year and all-time totals from the local history")
+                .arg(Arg::with_name("username").required(true)),
+        )
+        .arg(
+            Arg::with_name("username")
+                .help("defaults to the user of the configuration file")
+                .multiple(true),
+        )
+        .arg(
+            Arg::with_name("users-file")
+                .help("file with one username per line to compare")
+                .value_name("path")
+                .long("users-file"),
+        )
+        .arg(
+            Arg::with_name("team")
+                .help("summarizes a team defined in the configuration file")
+                .value_name("name")
+                .long("team")
+                .conflicts_with_all(&["username", "users-file"]),
+        )
+        .arg(
+            Arg::with_name("sort")
+                .help("column to sort the comparison by, largest first")
+                .value_name("column")
+                .long("sort")
+                .possible_values(&sort_columns()),
+        )
+        .arg(
+            Arg::with_name("reverse")
+                .help("reverses the --sort order")
+                .long("reverse")
+                .requires("sort"),
+        )
         .arg(
             Arg::with_name("date")
-                .help("date")
+                .global(true)
+                .help("date the stats are computed for, defaults to today or --to")
                 .value_name("date")
                 .short("d"),
         )
+        .arg(
+            Arg::with_name("from")
+                .global(true)
+                .help("first day to fetch")
+                .value_name("date")
+                .long("from"),
+        )
+        .arg(
+            Arg::with_name("to")
+                .global(true)
+                .help("last day to fetch")
+                .value_name("date")
+                .long("to"),
+        )
+        .arg(
+            Arg::with_name("year")
+                .global(true)
+                .help("fetches the calendar year instead of the last year")
+                .value_name("YYYY")
+                .long("year")
+                .conflicts_with_all(&["from", "to"]),
+        )
+        .arg(
+            Arg::with_name("week-mode")
+                .help(
+                    "rolling: the 7 days up to the date, iso: Monday to Sunday, calendar: from \
+                     --week-start. Defaults to calendar if a week start is set, rolling otherwise",
+                )
+                .value_name("mode")
+                .long("week-mode")
+                .possible_values(WEEK_MODES),
+        )
+        .arg(
+            Arg::with_name("week-start")
+                .help("first day of calendar weeks, defaults to monday")
+                .value_name("day")
+                .long("week-start")
+                .possible_values(&[
+                    "monday",
+                    "tuesday",
+                    "wednesday",
+                    "thursday",
+                    "friday",
+                    "saturday",
+                    "sunday",
+                ]),
+        )
+        .arg(
+            Arg::with_name("week")
+                .help("reports ISO week N of the year of the date as the week")
+                .value_name("N")
+                .long("week")
+                .conflicts_with("week-mode"),
+        )
+        .arg(
+            Arg::with_name("period")
+                .global(true)
+                .help(
+                    "sums a period instead, or the periods tui switches between: Nd, mtd, qtd, \
+                     ytd, fy or YYYY-MM-DD..YYYY-MM-DD",
+                )
+                .value_name("period")
+                .long("period")
+                .short("p")
+                .multiple(true)
+                .number_of_values(1)
+                .conflicts_with("team"),
+        )
+        .arg(
+            Arg::with_name("fiscal-start")
+                .global(true)
+                .help("month the fiscal year of --period fy starts in, 1 to 12")
+                .value_name("month")
+                .long("fiscal-start")
+                .default_value("4")
+                .validator(|month| match month.parse::<u32>() {
+                    Ok(1..=12) => Ok(()),
+                    _ => Err("expected a month from 1 to 12".to_string()),
+                }),
+        )
+        .arg(
+            Arg::with_name("graph")
+                .help("draws the calendar as a heatmap instead of printing the summary")
+                .long("graph")
+                .short("g")
+                .conflicts_with_all(&["team", "period"]),
+        )
+        .arg(
+            Arg::with_name("trend")
+                .help("draws contributions per day, week or month instead of printing the summary")
+                .value_name("granularity")
+                .long("trend")
+                .possible_values(&["daily", "weekly", "monthly"])
+                .conflicts_with_all(&["team", "period", "graph"]),
+        )
+        .arg(
+            Arg::with_name("color")
+                .help("colors of --graph, auto guesses them from the terminal")
+                .value_name("when")
+                .long("color")
+                .possible_values(&["auto", "truecolor", "256", "16", "never"])
+                .default_value("auto"),
+        )
+        .arg(
+            Arg::with_name("format")
+                .global(true)
+                .help("output format, defaults to text")
+                .value_name("format")
+                .long("format")
+                .short("f")
+                .possible_values(OutputFormat::NAMES),
+        )
+        .arg(
+            Arg::with_name("source")
+                .global(true)
+                .help("where to read contributions from, defaults to html")
+                .value_name("source")
+                .long("source")
+                .short("s")
+                .possible_values(SOURCES),
+        )
+        .arg(
+            Arg::with_name("token")
+                .global(true)
+                .help("GitHub token for the graphql source")
+                .value_name("token")
+                .long("token")
+                .env("GITHUB_TOKEN")
+                .hide_env_values(true),
+        )
+        .arg(
+            Arg::with_name("input")
+                .global(true)
+                .help(
+                    "file (or - for stdin) for the file source, which it selects by default, or directory for the recorded source",
+                )
+                .value_name("path")
+                .long("input")
+                .short("i"),
+        )
+        .arg(
+            Arg::with_name("host")
+                .global(true)
+                .help("host of a self-managed instance, or the endpoint of the graphql source")
+                .value_name("host")
+                .long("host"),
+        )
+        .arg(
+            Arg::with_name("config")
+                .global(true)
+                .help("configuration file to read instead of ~/.config/contriview/config.toml")
+                .value_name("path")
+                .long("config"),
+        )
+        .arg(
+            Arg::with_name("timezone")
+                .global(true)
+                .help("IANA timezone today is taken in, defaults to the system one")
+                .value_name("zone")
+                .long("timezone")
+                .validator(|zone| {
+                    zone.parse::<chrono_tz::Tz>()
+                        .map(|_| ())
+                        .map_err(|_| format!("unknown timezone: {}", zone))
+                }),
+        )
+        .arg(
+            Arg::with_name("concurrency")
+                .global(true)
+                .help("users fetched at the same time")
+                .value_name("n")
+                .long("concurrency")
+                .default_value("4"),
+        )
+        .arg(
+            Arg::with_name("rate-limit")
+                .global(true)
+                .help("maximum requests per second to each host, 0 for no limit")
+                .value_name("per-second")
+                .long("rate-limit")
+                .default_value("0")
+                .validator(|rate| match rate.parse::<f64>() {
+                    Ok(rate) if rate == 0.0 || (MIN_RATE_LIMIT..=1000.0).contains(&rate) => Ok(()),
+                    _ => Err(format!(
+                        "expected 0 or a number of requests from {} to 1000",
+                        MIN_RATE_LIMIT
+                    )),
+                }),
+        )
+        .arg(
+            Arg::with_name("retries")
+                .global(true)
+                .help("retries of requests answered with 429 or 5xx")
+                .value_name("n")
+                .long("retries")
+                .default_value("3"),
+        )
+        .arg(
+            Arg::with_name("history")
+                .help("computes the stats from the history kept by sync instead of fetching")
+                .long("history")
+                .conflicts_with_all(&["offline", "refresh"]),
+        )
+        .arg(
+            Arg::with_name("history-db")
+                .global(true)
+                .help("history database, defaults to contriview/history.sqlite3 in the data directory")
+                .value_name("path")
+                .long("history-db"),
+        )
+        .arg(
+            Arg::with_name("offline")
+                .global(true)
+                .help("answers from the cache only, whatever the age of the entries")
+                .long("offline")
+                .conflicts_with("refresh"),
+        )
+        .arg(
+            Arg::with_name("refresh")
+                .global(true)
+                .help("fetches again even if the cache is fresh")
+                .long("refresh"),
+        )
+        .arg(
+            Arg::with_name("cache-ttl")
+                .global(true)
+                .help("seconds a cached calendar is used before asking the server again")
+                .value_name("seconds")
+                .long("cache-ttl")
+                .default_value("900"),
+        )
+        .arg(
+            Arg::with_name("git")
+                .global(true)
+                .help("count commits in local repositories instead of fetching")
+                .value_name("path")
+                .long("git")
+                .multiple(true)
+                .conflicts_with("source"),
+        )
+        .arg(
+            Arg::with_name("author")
+                .global(true)
+                .help("author email to count with --git, defaults to user.email")
+                .value_name("email")
+                .long("author")
+                .multiple(true)
+                .number_of_values(1)
+                .requires("git"),
+        )
+}
+
+fn sort_columns() -> Vec<&'static str> {
+    let mut columns = vec![USER_COLUMN];
+    columns.extend(
+        ContriView::FIELDS
+            .iter()
+            .map(|f| f.trim_end_matches("_contributions")),
+    );
+    columns
+}
+
+/// Reads usernames from `path`, skipping blank lines and `#` comments.
+fn users_from_file(path: &str) -> Result<Vec<String>> {
+    let text = std::fs::read_to_string(path).map_err(|source| ContriviewError::Io {
+        path: path.into(),
+        source,
+    })?;
+
+    Ok(text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
+
+fn write_file(path: &str, contents: &[u8]) -> Result<()> {
+    std::fs::write(path, contents).map_err(|source| ContriviewError::Io {
+        path: path.into(),
+        source,
+    })
 }
 
-fn date_from_string(date: &str) -> Date<Local> {
-    let v: Vec<u32> = date.split('-').map(|v| v.parse::<u32>().unwrap()).collect();
+fn open_history(matches: &ArgMatches) -> Result<History> {
+    match matches.value_of("history-db") {
+        Some(path) => History::open(path),
+        None => match History::default_path() {
+            Some(path) => History::open(path),
+            None => Err(ContriviewError::Config(
+                "no data directory for the history, use --history-db".to_string(),
+            )),
+        },
+    }
+}
+
+/// Returns true if the selected source reads a single calendar whatever the username.
+fn ignores_username(matches: &ArgMatches, settings: &Config) -> bool {
+    matches.is_present("git") || settings.source.as_deref() == Some("file")
+}
+
+/// Name the history keeps the days of the selected source under.
+fn history_source(matches: &ArgMatches, settings: &Config) -> String {
+    let name = if matches.is_present("git") {
+        "git"
+    } else {
+        match settings.source.as_deref().unwrap_or("html") {
+            "html" | "graphql" => "github",
+            other => other,
+        }
+    };
+
+    match &settings.host {
+        Some(host) => format!("{} {}", name, host),
+        None => name.to_string(),
+    }
+}
+
+fn http_client(matches: &ArgMatches) -> HttpClient {
+    let retry = RetryPolicy {
+        max_retries: value_t!(matches, "retries", u32).unwrap_or_else(|e| exit_usage(e)),
+        ..RetryPolicy::default()
+    };
+    let rate_limit = value_t!(matches, "rate-limit", f64).unwrap_or_else(|e| exit_usage(e));
+
+    HttpClient::new()
+        .with_retry(retry)
+        .with_rate_limit(rate_limit)
+}
+
+fn source(matches: &ArgMatches, settings: &Config) -> Result<Box<dyn ContributionSource>> {
+    let source = uncached_source(matches, settings)?;
+
+    let dir = match Cache::default_dir() {
+        Some(dir) => dir,
+        None => return Ok(source),
+    };
+    let mode = if matches.is_present("offline") {
+        CacheMode::Offline
+    } else if matches.is_present("refresh") {
+        CacheMode::Refresh
+    } else {
+        CacheMode::Normal
+    };
+    let ttl = value_t!(matches, "cache-ttl", u64).unwrap_or_else(|e| exit_usage(e));
+
+    Ok(Box::new(CachedSource::new(
+        source,
+        Cache::new(dir)
+            .with_ttl(std::time::Duration::from_secs(ttl))
+            .with_mode(mode),
+    )))
+}
+
+fn uncached_source(matches: &ArgMatches, settings: &Config) -> Result<Box<dyn ContributionSource>> {
+    if let Some(repos) = matches.values_of("git") {
+        let emails = matches.values_of("author").into_iter().flatten();
+        return Ok(Box::new(
+            GitSource::new(repos)
+                .with_emails(emails)
+                .with_today(settings.today()),
+        ));
+    }
+
+    let http = http_client(matches);
+    let today = settings.today();
+    let host = settings.host.as_deref();
+    let source = settings.source.as_deref().unwrap_or("html");
+    let required = |value: Option<&str>, what: &str| {
+        value
+            .map(String::from)
+            .ok_or_else(|| ContriviewError::Config(format!("the {} source needs {}", source, what)))
+    };
+
+    Ok(match source {
+        "graphql" => {
+            let token = required(settings.token.as_deref(), "a token")?;
+            let source = GraphqlClient::new(&token).with_http(http).with_today(today);
+            match host {
+                Some(endpoint) => Box::new(source.with_endpoint(endpoint)),
+                None => Box::new(source),
+            }
+        }
+        "gitlab" => {
+            let source = GitlabSource::new().with_http(http).with_today(today);
+            match host {
+                Some(host) => Box::new(source.with_host(host)),
+                None => Box::new(source),
+            }
+        }
+        "gitea" => {
+            let mut source = GiteaSource::new().with_http(http).with_today(today);
+            if let Some(tz) = settings.tz() {
+                source = source.with_timezone(tz);
+            }
+            match host {
+                Some(host) => Box::new(source.with_host(host)),
+                None => Box::new(source),
+            }
+        }
+        "file" => Box::new(FileSource::new(required(
+            matches.value_of("input"),
+            "--input",
+        )?)),
+        "recorded" => Box::new(RecordedSource::from_dir(required(
+            matches.value_of("input"),
+            "--input",
+        )?)),
+        _ => {
+            let source = GithubHtmlSource::new().with_http(http).with_today(today);
+            match host {
+                Some(host) => Box::new(source.with_host(host)),
+                None => Box::new(source),
+            }
+        }
+    })
+}
+
+/// The configuration file, or the one given with `--config`, overridden by the flags given on
+/// the command line. `--input` selects the file source unless a source reading it is set.
+fn settings(matches: &ArgMatches) -> Result<Config> {
+    let config = match matches.value_of("config") {
+        Some(path) => Config::from_file(path.as_ref())?,
+        None => Config::load()?,
+    };
+    let flag = |name| matches.value_of(name).map(String::from);
+
+    let mut settings = config.overridden_by(Config {
+        token: flag("token"),
+        source: flag("source"),
+        host: flag("host"),
+        format: flag("format"),
+        week_mode: flag("week-mode"),
+        week_start: flag("week-start"),
+        timezone: flag("timezone"),
+        ..Config::default()
+    });
+    if matches.is_present("input")
+        && !matches!(settings.source.as_deref(), Some("file" | "recorded"))
+    {
+        if matches.is_present("source") {
+            return Err(ContriviewError::Config(
+                "--input only applies to the file and recorded sources".to_string(),
+            ));
+        }
+        settings.source = Some("file".to_string());
+    }
+
+    Ok(settings)
+}
+
+fn color_mode(matches: &ArgMatches) -> ColorMode {
+    match matches.value_of("color").unwrap() {
+        "auto" if std::io::stdout().is_terminal() => ColorMode::detect(),
+        "auto" => ColorMode::NoColor,
+        name => name.parse().unwrap(),
+    }
+}
+
+fn week_mode(matches: &ArgMatches, settings: &Config, date: NaiveDate) -> Result<WeekMode> {
+    if matches.is_present("week") {
+        let week = value_t!(matches, "week", u32).unwrap_or_else(|e| exit_usage(e));
+        let mode = WeekMode::IsoWeek(week);
+        return match mode.window(date) {
+            Some(_) => Ok(mode),
+            None => Err(ContriviewError::InvalidWeek(week)),
+        };
+    }
+
+    // A configured first day of the week only matters to calendar weeks, so it implies them.
+    let mode = match (&settings.week_mode, &settings.week_start) {
+        (Some(mode), _) => mode.as_str(),
+        (None, Some(_)) => "calendar",
+        (None, None) => "rolling",
+    };
+
+    Ok(match mode {
+        "iso" => WeekMode::ISO,
+        "calendar" => {
+            let start = settings.week_start.as_deref().unwrap_or("monday");
+            WeekMode::Calendar(start.parse().unwrap())
+        }
+        _ => WeekMode::Rolling,
+    })
+}
 
-    Local.ymd(v[0] as i32, v[1], v[2])
+fn date_from_string(date: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| ContriviewError::InvalidDate(date.to_string()))
 }
@@ -0,0 +1,255 @@
+//! Parsing of the GitHub contribution calendar markup.
+//!
+//! Two layouts are recognized:
+//!
+//! * [`Layout::LegacySvg`]: `<rect data-date data-count>` cells of an SVG graph, with the
+//!   intensity in `data-level` or in the `fill` color.
+//! * [`Layout::Table`]: `<td class="ContributionCalendar-day" data-date data-level>` cells
+//!   whose counts only appear in tooltip text such as "5 contributions on January 3rd.".
+
+use crate::calendar::{ContributionCalendar, ContributionDay};
+use crate::error::{ContriviewError, Result};
+use chrono::prelude::*;
+use scraper::*;
+use std::collections::HashMap;
+
+/// Markup variants of the contribution calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// The SVG graph served until 2022.
+    LegacySvg,
+    /// The table graph with tooltip counts.
+    Table,
+}
+
+/// Detects the layout of a contributions page.
+pub fn detect_layout(html: &str) -> Option<Layout> {
+    detect(&Html::parse_document(html))
+}
+
+/// Parses a contributions page in any supported layout.
+pub fn parse_html(html: &str) -> Result<ContributionCalendar> {
+    let doc = Html::parse_document(html);
+
+    let days = match detect(&doc) {
+        Some(Layout::LegacySvg) => parse_legacy_svg(&doc)?,
+        Some(Layout::Table) => parse_table(&doc)?,
+        None => {
+            return Err(ContriviewError::MarkupChanged(
+                "no contribution days found".to_string(),
+            ))
+        }
+    };
+
+    Ok(ContributionCalendar::new(days))
+}
+
+fn detect(doc: &Html) -> Option<Layout> {
+    if doc.select(&selector("rect[data-date]")).next().is_some() {
+        Some(Layout::LegacySvg)
+    } else if doc
+        .select(&selector("td.ContributionCalendar-day[data-date]"))
+        .next()
+        .is_some()
+    {
+        Some(Layout::Table)
+    } else {
+        None
+    }
+}
+
+fn parse_legacy_svg(doc: &Html) -> Result<Vec<ContributionDay>> {
+    doc.select(&selector("rect[data-date]"))
+        .map(|i| {
+            let e = i.value();
+            let date = parse_date(e)?;
+            let count = e
+                .attr("data-count")
+                .and_then(|c| c.parse().ok())
+                .ok_or_else(|| {
+                    ContriviewError::MarkupChanged(format!("missing data-count on {}", date))
+                })?;
+            let level = e
+                .attr("data-level")
+                .and_then(|l| l.parse().ok())
+                .or_else(|| e.attr("fill").and_then(level_from_fill))
+                .unwrap_or_default();
+
+            Ok(ContributionDay { date, count, level })
+        })
+        .collect()
+}
+
+fn parse_table(doc: &Html) -> Result<Vec<ContributionDay>> {
+    let tooltips: HashMap<&str, String> = doc
+        .select(&selector("tool-tip[for]"))
+        .filter_map(|t| Some((t.value().attr("for")?, t.text().collect())))
+        .collect();
+
+    doc.select(&selector("td.ContributionCalendar-day[data-date]"))
+        .map(|i| {
+            let e = i.value();
+            let date = parse_date(e)?;
+            let level = e
+                .attr("data-level")
+                .and_then(|l| l.parse().ok())
+                .unwrap_or_default();
+
+            // Older table layouts kept the tooltip text inside the cell itself.
+            let text = match e.id().and_then(|id| tooltips.get(id)) {
+                Some(text) => text.clone(),
+                None => i.text().collect(),
+            };
+            let count = count_from_tooltip(&text).ok_or_else(|| {
+                ContriviewError::MarkupChanged(format!(
+                    "unreadable contribution count {:?} on {}",
+                    text.trim(),
+                    date
+                ))
+            })?;
+
+            Ok(ContributionDay { date, count, level })
+        })
+        .collect()
+}
+
+fn parse_date(e: &node::Element) -> Result<NaiveDate> {
+    let date = e.attr("data-date").unwrap_or_default();
+
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| ContriviewError::MarkupChanged(format!("invalid data-date {:?}", date)))
+}
+
+/// Reads the count out of "No contributions on ...", "1 contribution on ..." or
+/// "1,234 contributions on ...".
+fn count_from_tooltip(text: &str) -> Option<u32> {
+    let first = text.split_whitespace().next()?;
+
+    if first == "No" {
+        return Some(0);
+    }
+
+    first.replace(',', "").parse().ok()
+}
+
+/// Fill colors of the legacy calendar, indexed by intensity level.
+pub(crate) const PALETTE: [&str; 5] = ["#ebedf0", "#c6e48b", "#7bc96f", "#239a3b", "#196127"];
+
+/// Maps the legacy calendar palette to an intensity level.
+fn level_from_fill(fill: &str) -> Option<u8> {
+    PALETTE
+        .iter()
+        .position(|color| color.eq_ignore_ascii_case(fill))
+        .map(|level| level as u8)
+}
+
+fn selector(s: &str) -> Selector {
+    Selector::parse(s).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{date, sample_html, table_html};
+
+    #[test]
+    fn test_detect_layout() {
+        assert_eq!(Some(Layout::LegacySvg), detect_layout(sample_html()));
+        assert_eq!(Some(Layout::Table), detect_layout(table_html()));
+        assert_eq!(None, detect_layout("<html></html>"));
+    }
+
+    #[test]
+    fn test_parse_legacy_svg() {
+        let calendar = parse_html(sample_html()).unwrap();
+
+        assert_eq!(371, calendar.days().len());
+        assert_eq!(
+            Some(&ContributionDay {
+                date: date("2019-01-05"),
+                count: 33,
+                level: 3,
+            }),
+            calendar.get(date("2019-01-05"))
+        );
+    }
+
+    #[test]
+    fn test_parse_table() {
+        let calendar = parse_html(table_html()).unwrap();
+        let days = calendar.days();
+
+        assert_eq!(14, days.len());
+        assert_eq!(date("2023-01-01"), days[0].date);
+        assert_eq!(date("2023-01-14"), days[13].date);
+        assert_eq!(35, days.iter().map(|d| d.count).sum::<u32>());
+        assert_eq!(
+            Some(&ContributionDay {
+                date: date("2023-01-05"),
+                count: 12,
+                level: 4,
+            }),
+            calendar.get(date("2023-01-05"))
+        );
+        assert_eq!(1, calendar.get(date("2023-01-03")).unwrap().count);
+    }
+
+    #[test]
+    fn test_parse_table_inline_tooltip() {
+        let html = r#"<table><tr>
+  <td class="ContributionCalendar-day" data-date="2022-06-01" data-level="2"><span class="sr-only">1,024 contributions on June 1st</span></td>
+</tr></table>"#;
+
+        let calendar = parse_html(html).unwrap();
+
+        assert_eq!(1024, calendar.get(date("2022-06-01")).unwrap().count);
+    }
+
+    #[test]
+    fn test_count_from_tooltip() {
+        assert_eq!(
+            Some(0),
+            count_from_tooltip("No contributions on January 1st.")
+        );
+        assert_eq!(
+            Some(1),
+            count_from_tooltip("1 contribution on January 3rd.")
+        );
+        assert_eq!(
+            Some(5),
+            count_from_tooltip(" 5 contributions on January 3rd.")
+        );
+        assert_eq!(None, count_from_tooltip(""));
+        assert_eq!(None, count_from_tooltip("Many contributions"));
+    }
+
+    #[test]
+    fn test_parse_without_days() {
+        assert!(matches!(
+            parse_html("<html><body>Not Found</body></html>"),
+            Err(ContriviewError::MarkupChanged(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_legacy_svg_without_count() {
+        let html = r#"<svg><rect class="day" data-date="2019-01-01"/></svg>"#;
+
+        assert!(matches!(
+            parse_html(html),
+            Err(ContriviewError::MarkupChanged(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_table_without_tooltip() {
+        let html = r#"<table><tr>
+  <td class="ContributionCalendar-day" data-date="2023-01-01" data-level="0" id="day-0"></td>
+</tr></table>"#;
+
+        assert!(matches!(
+            parse_html(html),
+            Err(ContriviewError::MarkupChanged(_))
+        ));
+    }
+}
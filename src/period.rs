@@ -0,0 +1,391 @@
+//! Sums over arbitrary periods of one calendar.
+
+use crate::calendar::{ContributionCalendar, DateRange};
+use crate::compare::{format_value, write_table};
+use crate::error::{ContriviewError, Result};
+use chrono::prelude::*;
+use serde::Serialize;
+use std::fmt;
+
+/// A window of days ending on, or independent of, the target date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    /// The last `n` days, the target date included. Written `30d`.
+    LastDays(u32),
+    /// From the first of the month. Written `mtd`.
+    MonthToDate,
+    /// From the first day of the quarter. Written `qtd`.
+    QuarterToDate,
+    /// From January 1st. Written `ytd`.
+    YearToDate,
+    /// From the start of the fiscal year, which begins on the first of `start_month`.
+    /// Written `fy`.
+    FiscalYearToDate {
+        /// Month the fiscal year starts in, 1 to 12.
+        start_month: u32,
+    },
+    /// Fixed days, both included. Written `2024-03-01..2024-06-30`.
+    Range(NaiveDate, NaiveDate),
+}
+
+impl Period {
+    /// Parses the written form of a period. `fy` uses `fiscal_start` as its first month.
+    pub fn parse(spec: &str, fiscal_start: u32) -> Result<Self> {
+        let invalid = || ContriviewError::InvalidPeriod(spec.to_string());
+
+        let period = match spec {
+            "mtd" => Period::MonthToDate,
+            "qtd" => Period::QuarterToDate,
+            "ytd" => Period::YearToDate,
+            "fy" if (1..=12).contains(&fiscal_start) => Period::FiscalYearToDate {
+                start_month: fiscal_start,
+            },
+            _ => {
+                if let Some((from, to)) = spec.split_once("..") {
+                    let parse = |s| NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| invalid());
+                    let (from, to) = (parse(from)?, parse(to)?);
+                    if from > to {
+                        return Err(invalid());
+                    }
+                    Period::Range(from, to)
+                } else {
+                    let days: u32 = spec
+                        .strip_suffix('d')
+                        .and_then(|n| n.parse().ok())
+                        .ok_or_else(invalid)?;
+                    if days == 0 {
+                        return Err(invalid());
+                    }
+                    Period::LastDays(days)
+                }
+            }
+        };
+
+        Ok(period)
+    }
+
+    /// First and last day of the period for the target `date`. Fails if the period starts
+    /// before the earliest date there is.
+    pub fn window(&self, date: NaiveDate) -> Result<(NaiveDate, NaiveDate)> {
+        let first_of = |year, month| NaiveDate::from_ymd_opt(year, month, 1).unwrap();
+
+        let from = match *self {
+            Period::LastDays(days) => date
+                .checked_sub_signed(chrono::Duration::days(i64::from(days) - 1))
+                .ok_or_else(|| ContriviewError::InvalidPeriod(self.to_string()))?,
+            Period::MonthToDate => first_of(date.year(), date.month()),
+            Period::QuarterToDate => first_of(date.year(), date.month0() / 3 * 3 + 1),
+            Period::YearToDate => first_of(date.year(), 1),
+            Period::FiscalYearToDate { start_month } => {
+                if date.month() >= start_month {
+                    first_of(date.year(), start_month)
+                } else {
+                    first_of(date.year() - 1, start_month)
+                }
+            }
+            Period::Range(from, to) => return Ok((from, to)),
+        };
+
+        Ok((from, date))
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Period::LastDays(days) => write!(f, "{}d", days),
+            Period::MonthToDate => write!(f, "mtd"),
+            Period::QuarterToDate => write!(f, "qtd"),
+            Period::YearToDate => write!(f, "ytd"),
+            Period::FiscalYearToDate { .. } => write!(f, "fy"),
+            Period::Range(from, to) => write!(f, "{}..{}", from, to),
+        }
+    }
+}
+
+/// Contributions over one period.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PeriodSummary {
+    /// The period as written on the command line.
+    pub period: String,
+    /// First day of the period.
+    pub from: NaiveDate,
+    /// Last day of the period.
+    pub to: NaiveDate,
+    /// Contributions in the period.
+    pub sum: u32,
+    /// Daily average over every day of the period, counting days missing from the calendar as
+    /// days without contributions.
+    pub average: f64,
+    /// Days of the period with at least one contribution.
+    pub active_days: u32,
+}
+
+impl PeriodSummary {
+    /// Sums `calendar` over `period` for the target `date`.
+    pub fn new(calendar: &ContributionCalendar, period: &Period, date: NaiveDate) -> Result<Self> {
+        let (from, to) = period.window(date)?;
+        let days = calendar.within(&DateRange::new(from, to));
+        let sum = days.days().iter().map(|d| d.count).sum();
+        let length = (to - from).num_days() + 1;
+        let average = if length > 0 {
+            f64::from(sum) / length as f64
+        } else {
+            0.0
+        };
+
+        Ok(PeriodSummary {
+            period: period.to_string(),
+            from,
+            to,
+            sum,
+            average,
+            active_days: days.days().iter().filter(|d| d.count > 0).count() as u32,
+        })
+    }
+}
+
+/// Period summaries of one or more users, printed as a table.
+#[derive(Debug, Default)]
+pub struct PeriodTable {
+    rows: Vec<(String, Vec<PeriodSummary>)>,
+}
+
+impl PeriodTable {
+    /// Sums each calendar over every period.
+    pub fn new<'a>(
+        calendars: impl IntoIterator<Item = (String, &'a ContributionCalendar)>,
+        periods: &[Period],
+        date: NaiveDate,
+    ) -> Result<Self> {
+        let rows = calendars
+            .into_iter()
+            .map(|(user, calendar)| {
+                let summaries = periods
+                    .iter()
+                    .map(|p| PeriodSummary::new(calendar, p, date))
+                    .collect::<Result<_>>()?;
+                Ok((user, summaries))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(PeriodTable { rows })
+    }
+
+    /// Users and their summaries, in the order the periods were given.
+    pub fn rows(&self) -> &[(String, Vec<PeriodSummary>)] {
+        &self.rows
+    }
+}
+
+impl fmt::Display for PeriodTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut table = vec![[
+            "user",
+            "period",
+            "from",
+            "to",
+            "sum",
+            "average",
+            "active_days",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>()];
+
+        for (user, summaries) in &self.rows {
+            for summary in summaries {
+                table.push(vec![
+                    user.clone(),
+                    summary.period.clone(),
+                    summary.from.to_string(),
+                    summary.to.to_string(),
+                    summary.sum.to_string(),
+                    format_value(summary.average),
+                    summary.active_days.to_string(),
+                ]);
+            }
+        }
+
+        write_table(f, &table, 4, false)
+    }
+}
+
+/// The range to fetch so that every period can be summarized, or `None` if the last year,
+/// fetched by default, already covers them.
+pub fn covering_range(periods: &[Period], date: NaiveDate) -> Result<Option<DateRange>> {
+    let (year_from, year_to) = DateRange::default().or_last_year(date);
+    let windows = periods
+        .iter()
+        .map(|p| p.window(date))
+        .collect::<Result<Vec<_>>>()?;
+    let (from, to) = match (
+        windows.iter().map(|w| w.0).min(),
+        windows.iter().map(|w| w.1).max(),
+    ) {
+        (Some(from), Some(to)) => (from.min(year_from), to.max(year_to)),
+        _ => return Ok(None),
+    };
+
+    if from == year_from && to == year_to {
+        Ok(None)
+    } else {
+        Ok(Some(DateRange::new(from, to)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{date, sample_calendar};
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Period::LastDays(30), Period::parse("30d", 4).unwrap());
+        assert_eq!(Period::QuarterToDate, Period::parse("qtd", 4).unwrap());
+        assert_eq!(
+            Period::FiscalYearToDate { start_month: 10 },
+            Period::parse("fy", 10).unwrap()
+        );
+        assert_eq!(
+            Period::Range(date("2024-03-01"), date("2024-06-30")),
+            Period::parse("2024-03-01..2024-06-30", 4).unwrap()
+        );
+
+        for spec in &[
+            "0d",
+            "d",
+            "week",
+            "2024-06-30..2024-03-01",
+            "2024-03-01..",
+            "fy",
+        ] {
+            let fiscal_start = if *spec == "fy" { 13 } else { 4 };
+            assert!(
+                matches!(
+                    Period::parse(spec, fiscal_start),
+                    Err(ContriviewError::InvalidPeriod(_))
+                ),
+                "{}",
+                spec
+            );
+        }
+    }
+
+    #[test]
+    fn test_window() {
+        let today = date("2019-01-26");
+
+        assert_eq!(
+            (date("2018-12-28"), today),
+            Period::LastDays(30).window(today).unwrap()
+        );
+        assert_eq!(
+            (date("2019-01-01"), today),
+            Period::MonthToDate.window(today).unwrap()
+        );
+        assert_eq!(
+            (date("2018-07-01"), date("2018-08-15")),
+            Period::QuarterToDate.window(date("2018-08-15")).unwrap()
+        );
+        assert_eq!(
+            (date("2019-01-01"), today),
+            Period::YearToDate.window(today).unwrap()
+        );
+        assert_eq!(
+            (date("2018-04-01"), today),
+            Period::FiscalYearToDate { start_month: 4 }
+                .window(today)
+                .unwrap()
+        );
+        assert_eq!(
+            (date("2019-01-01"), today),
+            Period::FiscalYearToDate { start_month: 1 }
+                .window(today)
+                .unwrap()
+        );
+        assert!(matches!(
+            Period::LastDays(4_000_000_000).window(today),
+            Err(ContriviewError::InvalidPeriod(p)) if p == "4000000000d"
+        ));
+    }
+
+    #[test]
+    fn test_summary() {
+        let summary =
+            PeriodSummary::new(&sample_calendar(), &Period::LastDays(7), date("2019-01-26"))
+                .unwrap();
+
+        assert_eq!(
+            summary,
+            PeriodSummary {
+                period: "7d".to_string(),
+                from: date("2019-01-20"),
+                to: date("2019-01-26"),
+                sum: 51,
+                average: 51.0 / 7.0,
+                active_days: 5,
+            }
+        );
+    }
+
+    #[test]
+    fn test_summary_longer_than_calendar() {
+        let summary = PeriodSummary::new(
+            &sample_calendar(),
+            &Period::LastDays(400),
+            date("2019-01-26"),
+        )
+        .unwrap();
+
+        assert_eq!(3532, summary.sum);
+        assert_eq!(3532.0 / 400.0, summary.average);
+    }
+
+    #[test]
+    fn test_summary_outside_calendar() {
+        let period = Period::Range(date("2017-01-01"), date("2017-12-31"));
+        let summary = PeriodSummary::new(&sample_calendar(), &period, date("2019-01-26")).unwrap();
+
+        assert_eq!(0, summary.sum);
+        assert_eq!(0.0, summary.average);
+    }
+
+    #[test]
+    fn test_covering_range() {
+        let today = date("2019-01-26");
+
+        assert_eq!(
+            None,
+            covering_range(&[Period::LastDays(30), Period::YearToDate], today).unwrap()
+        );
+        assert_eq!(
+            Some(DateRange::new(date("2017-03-01"), today)),
+            covering_range(
+                &[Period::Range(date("2017-03-01"), date("2017-06-30"))],
+                today
+            )
+            .unwrap()
+        );
+        assert_eq!(None, covering_range(&[], today).unwrap());
+        assert!(covering_range(&[Period::LastDays(u32::MAX)], today).is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        let calendar = sample_calendar();
+        let table = PeriodTable::new(
+            vec![("k-nasa".to_string(), &calendar)],
+            &[Period::LastDays(7), Period::MonthToDate],
+            date("2019-01-26"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            table.to_string(),
+            "user    period  from        to          sum  average  active_days
+k-nasa  7d      2019-01-20  2019-01-26   51     7.29            5
+k-nasa  mtd     2019-01-01  2019-01-26  260       10           20"
+        );
+    }
+}
@@ -0,0 +1,240 @@
+//! Where contribution calendars come from.
+
+use crate::calendar::{ContributionCalendar, DateRange};
+use crate::error::{ContriviewError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// A provider of contribution calendars.
+pub trait ContributionSource: Send + Sync {
+    /// Fetches the calendar of `username` restricted to `range`.
+    fn fetch(&self, username: &str, range: &DateRange) -> Result<ContributionCalendar>;
+
+    /// Identifies where the calendars come from in cache keys, e.g. the host.
+    ///
+    /// Sources returning `None`, the default, read local data and are never cached.
+    fn cache_id(&self) -> Option<String> {
+        None
+    }
+
+    /// Fetches the calendar unless it is unchanged since `validators` were issued.
+    ///
+    /// The default ignores the validators and always fetches.
+    fn fetch_if_modified(
+        &self,
+        username: &str,
+        range: &DateRange,
+        validators: &Validators,
+    ) -> Result<Fetched> {
+        let _ = validators;
+        Ok(Fetched::Modified(
+            self.fetch(username, range)?,
+            Validators::default(),
+        ))
+    }
+}
+
+/// HTTP validators of a fetched calendar, sent back to ask whether it changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Validators {
+    /// `ETag` header of the response.
+    pub etag: Option<String>,
+    /// `Last-Modified` header of the response.
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    /// Returns true if there is nothing to revalidate with.
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+/// Outcome of [`ContributionSource::fetch_if_modified`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fetched {
+    /// The calendar did not change.
+    NotModified,
+    /// A new calendar and the validators to revalidate it with.
+    Modified(ContributionCalendar, Validators),
+}
+
+/// Reads a saved contributions page or a JSON report from a file, or from stdin for `-`.
+///
+/// The username is ignored: the file holds a single calendar.
+#[derive(Debug, Clone)]
+pub struct FileSource {
+    path: PathBuf,
+}
+
+impl FileSource {
+    /// Reads from `path`.
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        FileSource { path: path.into() }
+    }
+}
+
+impl ContributionSource for FileSource {
+    fn fetch(&self, _username: &str, range: &DateRange) -> Result<ContributionCalendar> {
+        let text = if self.path == Path::new("-") {
+            let mut text = String::new();
+            std::io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|source| ContriviewError::Io {
+                    path: self.path.clone(),
+                    source,
+                })?;
+            text
+        } else {
+            read_file(&self.path)?
+        };
+
+        Ok(calendar_from_text(&text)?.within(range))
+    }
+}
+
+/// Serves calendars recorded ahead of time, either in memory or as `<username>.html` /
+/// `<username>.json` files in a directory.
+#[derive(Debug, Clone, Default)]
+pub struct RecordedSource {
+    calendars: HashMap<String, ContributionCalendar>,
+    dir: Option<PathBuf>,
+}
+
+impl RecordedSource {
+    /// An empty recording.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Looks up users without an in-memory calendar in `dir`.
+    pub fn from_dir<P: Into<PathBuf>>(dir: P) -> Self {
+        RecordedSource {
+            calendars: HashMap::new(),
+            dir: Some(dir.into()),
+        }
+    }
+
+    /// Records `calendar` for `username`.
+    pub fn with(mut self, username: &str, calendar: ContributionCalendar) -> Self {
+        self.calendars.insert(username.to_string(), calendar);
+        self
+    }
+}
+
+impl ContributionSource for RecordedSource {
+    fn fetch(&self, username: &str, range: &DateRange) -> Result<ContributionCalendar> {
+        if let Some(calendar) = self.calendars.get(username) {
+            return Ok(calendar.within(range));
+        }
+
+        let dir = match &self.dir {
+            Some(dir) => dir,
+            None => return Err(ContriviewError::UserNotFound(username.to_string())),
+        };
+
+        for extension in &["json", "html"] {
+            let path = dir.join(format!("{}.{}", username, extension));
+            if path.is_file() {
+                return Ok(calendar_from_text(&read_file(&path)?)?.within(range));
+            }
+        }
+
+        Err(ContriviewError::UserNotFound(username.to_string()))
+    }
+}
+
+#[derive(Deserialize)]
+struct SavedReport {
+    calendar: ContributionCalendar,
+}
+
+/// Parses a contributions page, a JSON report written by `--format json` or a bare JSON
+/// array of days.
+pub fn calendar_from_text(text: &str) -> Result<ContributionCalendar> {
+    let invalid_json =
+        |e: serde_json::Error| ContriviewError::MarkupChanged(format!("invalid JSON: {}", e));
+
+    match text.trim_start().chars().next() {
+        Some('{') => Ok(serde_json::from_str::<SavedReport>(text)
+            .map_err(invalid_json)?
+            .calendar),
+        Some('[') => serde_json::from_str(text).map_err(invalid_json),
+        _ => ContributionCalendar::from_html(text),
+    }
+}
+
+fn read_file(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).map_err(|source| ContriviewError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contriview::ContriView;
+    use crate::fixtures::{sample_calendar, sample_html, TempDir};
+    use crate::format::{render, OutputFormat, Report};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_calendar_from_text() {
+        let calendar = sample_calendar();
+        let view =
+            ContriView::from_calendar(&calendar, NaiveDate::from_ymd_opt(2019, 1, 26).unwrap());
+        let report = render(&Report::new(&view, &calendar), OutputFormat::Json).unwrap();
+        let days = serde_json::to_string(&calendar).unwrap();
+
+        assert_eq!(calendar, calendar_from_text(sample_html()).unwrap());
+        assert_eq!(calendar, calendar_from_text(&report).unwrap());
+        assert_eq!(calendar, calendar_from_text(&days).unwrap());
+        assert!(matches!(
+            calendar_from_text("{\"calendar\": 1}"),
+            Err(ContriviewError::MarkupChanged(_))
+        ));
+    }
+
+    #[test]
+    fn test_file_source() {
+        let dir = TempDir::new("file-source");
+        let path = dir.path().join("contributions.html");
+        std::fs::write(&path, sample_html()).unwrap();
+        let range = DateRange::new(
+            NaiveDate::from_ymd_opt(2019, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2019, 1, 31).unwrap(),
+        );
+
+        let calendar = FileSource::new(&path).fetch("anyone", &range).unwrap();
+
+        assert_eq!(26, calendar.days().len());
+        assert!(matches!(
+            FileSource::new(dir.path().join("missing.html")).fetch("anyone", &range),
+            Err(ContriviewError::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn test_recorded_source() {
+        let dir = TempDir::new("recorded-source");
+        std::fs::write(dir.path().join("k-nasa.html"), sample_html()).unwrap();
+        let source =
+            RecordedSource::from_dir(dir.path()).with("empty", ContributionCalendar::default());
+
+        assert_eq!(
+            sample_calendar(),
+            source.fetch("k-nasa", &DateRange::default()).unwrap()
+        );
+        assert!(source
+            .fetch("empty", &DateRange::default())
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            source.fetch("nobody", &DateRange::default()),
+            Err(ContriviewError::UserNotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,262 @@
+//! Contribution counts over the periods reported by [`ContriView`](crate::ContriView).
+
+use crate::calendar::{ContributionCalendar, ContributionDay};
+use crate::week::WeekMode;
+use chrono::*;
+
+/// Sum of every day in the calendar.
+pub fn sum_contributions(calendar: &ContributionCalendar) -> u32 {
+    calendar.days().iter().map(|d| d.count).sum()
+}
+
+/// Sum of the days in the same month as `date`.
+pub fn month_contributions(calendar: &ContributionCalendar, date: NaiveDate) -> u32 {
+    calendar
+        .days()
+        .iter()
+        .filter(|d| d.date.year() == date.year() && d.date.month() == date.month())
+        .map(|d| d.count)
+        .sum()
+}
+
+/// Sum of the days of the week selected by `week` for `date`, up to `date`.
+pub fn week_contributions(calendar: &ContributionCalendar, date: NaiveDate, week: WeekMode) -> u32 {
+    week_days(calendar, date, week).map(|d| d.count).sum()
+}
+
+/// Days of the calendar in the week of `date`, excluding the days after `date`.
+fn week_days(
+    calendar: &ContributionCalendar,
+    date: NaiveDate,
+    week: WeekMode,
+) -> impl Iterator<Item = &ContributionDay> {
+    let window = week.window(date);
+
+    calendar.days().iter().filter(move |d| {
+        d.date <= date && window.is_some_and(|(from, to)| from <= d.date && d.date <= to)
+    })
+}
+
+/// Sum of the days in the same year as `date`.
+pub fn year_contributions(calendar: &ContributionCalendar, date: NaiveDate) -> u32 {
+    calendar
+        .days()
+        .iter()
+        .filter(|d| d.date.year() == date.year())
+        .map(|d| d.count)
+        .sum()
+}
+
+/// Contributions made on `date`, or 0 when the day is not in the calendar.
+pub fn today_contributions(calendar: &ContributionCalendar, date: NaiveDate) -> u32 {
+    calendar.get(date).map(|d| d.count).unwrap_or_default()
+}
+
+/// Daily average over the days of the calendar counted by [`week_contributions`].
+pub fn week_average(calendar: &ContributionCalendar, date: NaiveDate, week: WeekMode) -> f64 {
+    average(
+        week_contributions(calendar, date, week),
+        week_days(calendar, date, week).count(),
+    )
+}
+
+/// Daily average over the days of the calendar in the same month as `date`.
+pub fn month_average(calendar: &ContributionCalendar, date: NaiveDate) -> f64 {
+    let days = calendar
+        .days()
+        .iter()
+        .filter(|d| d.date.year() == date.year() && d.date.month() == date.month())
+        .count();
+
+    average(month_contributions(calendar, date), days)
+}
+
+/// Daily average over every day of the calendar.
+pub fn daily_average(calendar: &ContributionCalendar) -> f64 {
+    average(sum_contributions(calendar), calendar.days().len())
+}
+
+/// Daily average over the days with at least one contribution.
+pub fn active_day_average(calendar: &ContributionCalendar) -> f64 {
+    let active = calendar.days().iter().filter(|d| d.count > 0).count();
+
+    average(sum_contributions(calendar), active)
+}
+
+/// Median of the daily counts.
+pub fn median(calendar: &ContributionCalendar) -> f64 {
+    percentile(calendar, 50.0)
+}
+
+/// Population standard deviation of the daily counts.
+pub fn standard_deviation(calendar: &ContributionCalendar) -> f64 {
+    let days = calendar.days();
+    if days.is_empty() {
+        return 0.0;
+    }
+
+    let mean = daily_average(calendar);
+    let variance = days
+        .iter()
+        .map(|d| (f64::from(d.count) - mean).powi(2))
+        .sum::<f64>()
+        / days.len() as f64;
+
+    variance.sqrt()
+}
+
+/// The `p`th percentile (0 to 100) of the daily counts, interpolated between the closest ranks.
+pub fn percentile(calendar: &ContributionCalendar, p: f64) -> f64 {
+    let mut counts: Vec<u32> = calendar.days().iter().map(|d| d.count).collect();
+    if counts.is_empty() {
+        return 0.0;
+    }
+    counts.sort_unstable();
+
+    let rank = p.clamp(0.0, 100.0) / 100.0 * (counts.len() - 1) as f64;
+    let lower = f64::from(counts[rank.floor() as usize]);
+    let upper = f64::from(counts[rank.ceil() as usize]);
+
+    lower + (upper - lower) * rank.fract()
+}
+
+fn average(sum: u32, days: usize) -> f64 {
+    if days == 0 {
+        0.0
+    } else {
+        f64::from(sum) / days as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::sample_calendar;
+
+    #[test]
+    fn test_sum_contributions() {
+        assert_eq!(3532, sum_contributions(&sample_calendar()))
+    }
+
+    #[test]
+    fn test_week_contributions() {
+        let date = NaiveDate::from_ymd_opt(2019, 1, 26).unwrap();
+
+        assert_eq!(
+            51,
+            week_contributions(&sample_calendar(), date, WeekMode::Rolling)
+        )
+    }
+
+    #[test]
+    fn test_week_contributions_follows_date() {
+        let calendar = sample_calendar();
+        let wednesday = NaiveDate::from_ymd_opt(2019, 1, 23).unwrap();
+        let sunday = NaiveDate::from_ymd_opt(2019, 1, 20).unwrap();
+
+        assert_eq!(
+            145,
+            week_contributions(&calendar, wednesday, WeekMode::Rolling)
+        );
+        assert_eq!(46, week_contributions(&calendar, wednesday, WeekMode::ISO));
+        assert_eq!(111, week_contributions(&calendar, sunday, WeekMode::ISO));
+        assert_eq!(
+            0,
+            week_contributions(&calendar, sunday, WeekMode::Calendar(Weekday::Sun))
+        );
+        // Only the days up to Wednesday count towards the average.
+        assert_eq!(
+            46.0 / 3.0,
+            week_average(&calendar, wednesday, WeekMode::ISO)
+        );
+    }
+
+    #[test]
+    fn test_iso_week_contributions() {
+        let calendar = sample_calendar();
+        let date = NaiveDate::from_ymd_opt(2019, 1, 26).unwrap();
+
+        assert_eq!(
+            62,
+            week_contributions(&calendar, date, WeekMode::IsoWeek(1))
+        );
+        assert_eq!(
+            0,
+            week_contributions(&calendar, date, WeekMode::IsoWeek(53))
+        );
+    }
+
+    #[test]
+    fn test_today_contributions() {
+        let date = NaiveDate::from_ymd_opt(2019, 1, 26).unwrap();
+
+        assert_eq!(3, today_contributions(&sample_calendar(), date))
+    }
+    #[test]
+    fn test_year_contributions() {
+        let date = NaiveDate::from_ymd_opt(2019, 1, 26).unwrap();
+
+        assert_eq!(260, year_contributions(&sample_calendar(), date))
+    }
+
+    #[test]
+    fn test_month_contributions() {
+        let date = NaiveDate::from_ymd_opt(2019, 1, 26).unwrap();
+
+        assert_eq!(260, month_contributions(&sample_calendar(), date))
+    }
+
+    #[test]
+    fn test_averages() {
+        let calendar = sample_calendar();
+        let date = NaiveDate::from_ymd_opt(2019, 1, 26).unwrap();
+
+        assert_eq!(51.0 / 7.0, week_average(&calendar, date, WeekMode::Rolling));
+        assert_eq!(10.0, month_average(&calendar, date));
+        assert_eq!(3532.0 / 371.0, daily_average(&calendar));
+        assert_eq!(3532.0 / 278.0, active_day_average(&calendar));
+    }
+
+    #[test]
+    fn test_distribution() {
+        let calendar = sample_calendar();
+
+        assert_eq!(6.0, median(&calendar));
+        assert!((standard_deviation(&calendar) - 11.108215).abs() < 1e-6);
+        assert_eq!(16.0, percentile(&calendar, 75.0));
+        assert_eq!(25.0, percentile(&calendar, 90.0));
+        assert_eq!(44.0, percentile(&calendar, 99.0));
+        assert_eq!(0.0, percentile(&calendar, 0.0));
+    }
+
+    #[test]
+    fn test_interpolated_percentile() {
+        let calendar = ContributionCalendar::new(
+            [1, 2, 3, 10]
+                .iter()
+                .enumerate()
+                .map(|(i, &count)| ContributionDay {
+                    date: NaiveDate::from_ymd_opt(2019, 1, i as u32 + 1).unwrap(),
+                    count,
+                    level: 1,
+                })
+                .collect(),
+        );
+
+        assert_eq!(2.5, median(&calendar));
+        assert_eq!(4.75, percentile(&calendar, 75.0));
+        assert_eq!(10.0, percentile(&calendar, 100.0));
+    }
+
+    #[test]
+    fn test_empty_calendar() {
+        let calendar = ContributionCalendar::default();
+        let date = NaiveDate::from_ymd_opt(2019, 1, 26).unwrap();
+
+        assert_eq!(0.0, week_average(&calendar, date, WeekMode::Rolling));
+        assert_eq!(0.0, month_average(&calendar, date));
+        assert_eq!(0.0, median(&calendar));
+        assert_eq!(0.0, standard_deviation(&calendar));
+        assert_eq!(0.0, active_day_average(&calendar));
+    }
+}
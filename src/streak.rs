@@ -0,0 +1,196 @@
+//! Streaks of consecutive days with and without contributions.
+
+use crate::calendar::ContributionCalendar;
+use chrono::prelude::*;
+use serde::Serialize;
+
+/// Consecutive days, both ends included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Run {
+    /// First day of the run.
+    pub start: NaiveDate,
+    /// Last day of the run.
+    pub end: NaiveDate,
+}
+
+impl Run {
+    fn day(date: NaiveDate) -> Self {
+        Run {
+            start: date,
+            end: date,
+        }
+    }
+
+    /// Number of days in the run.
+    pub fn days(&self) -> u32 {
+        (self.end - self.start).num_days() as u32 + 1
+    }
+}
+
+/// Streaks and gaps of a calendar up to a given date.
+///
+/// Days missing from the calendar count as days without contributions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Streaks {
+    /// Active days up to the target date. A target date without contributions yet does not
+    /// break the streak, so this is the streak ending the day before in that case.
+    pub current_streak: Option<Run>,
+    /// Longest run of active days.
+    pub longest_streak: Option<Run>,
+    /// Days without contributions up to the target date.
+    pub current_gap: Option<Run>,
+    /// Longest run of days without contributions.
+    pub longest_gap: Option<Run>,
+}
+
+impl Streaks {
+    /// Scans `calendar` from its first day to `date`.
+    pub fn new(calendar: &ContributionCalendar, date: NaiveDate) -> Self {
+        let first = match calendar.days().first() {
+            Some(day) => day.date,
+            None => return Streaks::default(),
+        };
+
+        let mut streaks = Streaks::default();
+        let mut streak: Option<Run> = None;
+        let mut gap: Option<Run> = None;
+        let mut previous_streak = None;
+
+        for day in first.iter_days().take_while(|d| *d <= date) {
+            if calendar.get(day).is_some_and(|d| d.count > 0) {
+                if let Some(ended) = gap.take() {
+                    longest(&mut streaks.longest_gap, ended);
+                }
+                extend(&mut streak, day);
+            } else {
+                if let Some(ended) = streak.take() {
+                    longest(&mut streaks.longest_streak, ended);
+                    previous_streak = Some(ended);
+                }
+                extend(&mut gap, day);
+            }
+        }
+
+        if let Some(run) = streak {
+            longest(&mut streaks.longest_streak, run);
+            streaks.current_streak = Some(run);
+        }
+        if let Some(run) = gap {
+            longest(&mut streaks.longest_gap, run);
+            streaks.current_gap = Some(run);
+            if run.start == date {
+                streaks.current_streak = previous_streak;
+            }
+        }
+
+        streaks
+    }
+}
+
+fn extend(run: &mut Option<Run>, date: NaiveDate) {
+    match run {
+        Some(run) => run.end = date,
+        None => *run = Some(Run::day(date)),
+    }
+}
+
+/// Keeps `run` if it is longer than `longest`. Ties keep the earlier run.
+fn longest(longest: &mut Option<Run>, run: Run) {
+    if longest.is_none_or(|l| run.days() > l.days()) {
+        *longest = Some(run);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::ContributionDay;
+    use crate::fixtures::{date, sample_calendar};
+
+    fn run(start: &str, end: &str) -> Option<Run> {
+        Some(Run {
+            start: date(start),
+            end: date(end),
+        })
+    }
+
+    /// One day per count, starting on 2019-01-01.
+    fn calendar(counts: &[u32]) -> ContributionCalendar {
+        ContributionCalendar::new(
+            date("2019-01-01")
+                .iter_days()
+                .zip(counts)
+                .map(|(date, &count)| ContributionDay {
+                    date,
+                    count,
+                    level: 0,
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_streaks() {
+        let streaks = Streaks::new(&calendar(&[1, 2, 3, 0, 0, 0, 4, 5]), date("2019-01-08"));
+
+        assert_eq!(
+            streaks,
+            Streaks {
+                current_streak: run("2019-01-07", "2019-01-08"),
+                longest_streak: run("2019-01-01", "2019-01-03"),
+                current_gap: None,
+                longest_gap: run("2019-01-04", "2019-01-06"),
+            }
+        );
+    }
+
+    #[test]
+    fn test_today_without_contributions_keeps_streak() {
+        let streaks = Streaks::new(&calendar(&[0, 1, 1, 0]), date("2019-01-04"));
+
+        assert_eq!(run("2019-01-02", "2019-01-03"), streaks.current_streak);
+        assert_eq!(run("2019-01-04", "2019-01-04"), streaks.current_gap);
+
+        let streaks = Streaks::new(&calendar(&[0, 1, 1, 0, 0]), date("2019-01-05"));
+
+        assert_eq!(None, streaks.current_streak);
+        assert_eq!(2, streaks.current_gap.unwrap().days());
+    }
+
+    #[test]
+    fn test_days_after_target_are_ignored() {
+        let streaks = Streaks::new(&calendar(&[1, 1, 0, 1, 1, 1]), date("2019-01-02"));
+
+        assert_eq!(run("2019-01-01", "2019-01-02"), streaks.current_streak);
+        assert_eq!(run("2019-01-01", "2019-01-02"), streaks.longest_streak);
+        assert_eq!(None, streaks.longest_gap);
+    }
+
+    #[test]
+    fn test_missing_days_are_gaps() {
+        let calendar = ContributionCalendar::new(vec![ContributionDay {
+            date: date("2019-01-01"),
+            count: 1,
+            level: 1,
+        }]);
+
+        let streaks = Streaks::new(&calendar, date("2019-01-10"));
+
+        assert_eq!(run("2019-01-02", "2019-01-10"), streaks.current_gap);
+        assert_eq!(
+            Streaks::default(),
+            Streaks::new(&calendar, date("2018-12-31"))
+        );
+    }
+
+    #[test]
+    fn test_sample_calendar() {
+        let streaks = Streaks::new(&sample_calendar(), date("2019-01-26"));
+
+        assert_eq!(run("2019-01-26", "2019-01-26"), streaks.current_streak);
+        assert_eq!(run("2018-09-17", "2018-11-05"), streaks.longest_streak);
+        assert_eq!(50, streaks.longest_streak.unwrap().days());
+        assert_eq!(None, streaks.current_gap);
+        assert_eq!(run("2018-01-24", "2018-01-29"), streaks.longest_gap);
+    }
+}
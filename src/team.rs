@@ -0,0 +1,200 @@
+//! Aggregate statistics of a team of accounts.
+
+use crate::calendar::ContributionCalendar;
+use crate::contriview::ContriView;
+use crate::week::WeekMode;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::fmt;
+
+/// How much a member contributed to the team calendar.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MemberShare {
+    /// Name of the account.
+    pub username: String,
+    /// Contributions of the member over the team calendar.
+    pub contributions: u32,
+    /// Percentage of the team contributions, 0 when the team has none.
+    pub share: f64,
+}
+
+/// Statistics of the calendars of several accounts merged into one.
+#[derive(Debug, Clone)]
+pub struct TeamView {
+    name: String,
+    calendar: ContributionCalendar,
+    summary: ContriView,
+    members: Vec<MemberShare>,
+}
+
+impl TeamView {
+    /// Merges the calendars of `members` and summarizes them relative to `date`.
+    pub fn new(name: &str, members: &[(String, ContributionCalendar)], date: NaiveDate) -> Self {
+        Self::with_week(name, members, date, WeekMode::default())
+    }
+
+    /// Like [`new`](Self::new), with the week of the summary selected by `week`.
+    pub fn with_week(
+        name: &str,
+        members: &[(String, ContributionCalendar)],
+        date: NaiveDate,
+        week: WeekMode,
+    ) -> Self {
+        let calendar = ContributionCalendar::merge(members.iter().map(|(_, c)| c));
+        let summary = ContriView::from_calendar_with_week(&calendar, date, week);
+        let total = summary.sum_contributions();
+
+        let members = members
+            .iter()
+            .map(|(username, calendar)| {
+                let contributions = calendar.days().iter().map(|d| d.count).sum();
+                let share = if total == 0 {
+                    0.0
+                } else {
+                    f64::from(contributions) * 100.0 / f64::from(total)
+                };
+
+                MemberShare {
+                    username: username.clone(),
+                    contributions,
+                    share,
+                }
+            })
+            .collect();
+
+        TeamView {
+            name: name.to_string(),
+            calendar,
+            summary,
+            members,
+        }
+    }
+
+    /// Name of the team.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The merged calendar.
+    pub fn calendar(&self) -> &ContributionCalendar {
+        &self.calendar
+    }
+
+    /// Statistics of the merged calendar.
+    pub fn summary(&self) -> &ContriView {
+        &self.summary
+    }
+
+    /// Members in the order they were given.
+    pub fn members(&self) -> &[MemberShare] {
+        &self.members
+    }
+
+    /// Contributions in the merged calendar divided by the number of members.
+    pub fn average_per_member(&self) -> f64 {
+        if self.members.is_empty() {
+            return 0.0;
+        }
+
+        f64::from(self.summary.sum_contributions()) / self.members.len() as f64
+    }
+}
+
+impl fmt::Display for TeamView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "team: {} ({} members)", self.name, self.members.len())?;
+        writeln!(f, "{}", self.summary)?;
+        writeln!(f, "average_per_member: {:.2}", self.average_per_member())?;
+        write!(f, "members:")?;
+
+        let width = self
+            .members
+            .iter()
+            .map(|m| m.username.chars().count())
+            .max()
+            .unwrap_or(0);
+        let count_width = self
+            .members
+            .iter()
+            .map(|m| m.contributions.to_string().len())
+            .max()
+            .unwrap_or(0);
+
+        for member in &self.members {
+            write!(
+                f,
+                "\n  {:<width$}  {:>count_width$}  {:>6.2}%",
+                member.username,
+                member.contributions,
+                member.share,
+                width = width,
+                count_width = count_width
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::ContributionDay;
+    use crate::fixtures::date;
+
+    fn calendar(counts: &[(&str, u32)]) -> ContributionCalendar {
+        ContributionCalendar::new(
+            counts
+                .iter()
+                .map(|(d, count)| ContributionDay {
+                    date: date(d),
+                    count: *count,
+                    level: 1,
+                })
+                .collect(),
+        )
+    }
+
+    fn team() -> TeamView {
+        TeamView::new(
+            "backend",
+            &[
+                (
+                    "alice".to_string(),
+                    calendar(&[("2019-01-25", 2), ("2019-01-26", 4)]),
+                ),
+                ("bob".to_string(), calendar(&[("2019-01-26", 2)])),
+                ("carol".to_string(), calendar(&[])),
+            ],
+            date("2019-01-26"),
+        )
+    }
+
+    #[test]
+    fn test_team_view() {
+        let team = team();
+
+        assert_eq!(6, team.summary().today_contributions());
+        assert_eq!(8, team.summary().sum_contributions());
+        assert_eq!(2, team.calendar().days().len());
+        assert!((team.average_per_member() - 8.0 / 3.0).abs() < 1e-9);
+        assert_eq!(
+            vec![(6, 75.0), (2, 25.0), (0, 0.0)],
+            team.members()
+                .iter()
+                .map(|m| (m.contributions, m.share))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert!(team().to_string().ends_with(
+            "average_per_member: 2.67
+members:
+  alice  6   75.00%
+  bob    2   25.00%
+  carol  0    0.00%"
+        ));
+    }
+}
@@ -0,0 +1,341 @@
+//! Contributions summed per day, week or month, drawn as sparklines and bar charts.
+
+use crate::calendar::{ContributionCalendar, DateRange};
+use chrono::prelude::*;
+use serde::Serialize;
+use std::fmt;
+
+/// Columns of the longest bar.
+pub const BAR_WIDTH: u32 = 40;
+
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Partial blocks of one to seven eighths of a column.
+const EIGHTHS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// Size of the buckets of a [`Trend`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Granularity {
+    /// One bucket per day.
+    Daily,
+    /// One bucket per ISO week, Monday to Sunday.
+    Weekly,
+    /// One bucket per calendar month.
+    Monthly,
+}
+
+impl Granularity {
+    /// First day of the bucket containing `date`.
+    fn bucket_start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Daily => date,
+            Granularity::Weekly => {
+                date - chrono::Duration::days(i64::from(date.weekday().num_days_from_monday()))
+            }
+            Granularity::Monthly => date.with_day(1).unwrap(),
+        }
+    }
+
+    /// Last day of the bucket starting on `start`.
+    fn bucket_end(self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Granularity::Daily => start,
+            Granularity::Weekly => start + chrono::Duration::days(6),
+            Granularity::Monthly => {
+                let (year, month) = match start.month() {
+                    12 => (start.year() + 1, 1),
+                    month => (start.year(), month + 1),
+                };
+                NaiveDate::from_ymd_opt(year, month, 1)
+                    .unwrap()
+                    .pred_opt()
+                    .unwrap()
+            }
+        }
+    }
+
+    fn label(self, start: NaiveDate) -> String {
+        match self {
+            Granularity::Daily => start.to_string(),
+            Granularity::Weekly => {
+                let week = start.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            }
+            Granularity::Monthly => start.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Granularity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Granularity::Daily => write!(f, "daily"),
+            Granularity::Weekly => write!(f, "weekly"),
+            Granularity::Monthly => write!(f, "monthly"),
+        }
+    }
+}
+
+/// Contributions of one day, week or month.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Bucket {
+    /// The day, `YYYY-Www` week or `YYYY-MM` month.
+    pub label: String,
+    /// First day counted, later than the start of the week or month at the start of the range.
+    pub from: NaiveDate,
+    /// Last day counted, earlier than its end at the end of the range.
+    pub to: NaiveDate,
+    /// Contributions from `from` to `to`.
+    pub contributions: u32,
+}
+
+/// Contributions of a calendar over a range, summed per bucket.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Trend {
+    username: String,
+    granularity: Granularity,
+    buckets: Vec<Bucket>,
+}
+
+impl Trend {
+    /// Sums the days of `calendar` from `from` to `to` into buckets of `granularity`. Buckets
+    /// cut by the range only count the days within it.
+    pub fn new(
+        username: &str,
+        calendar: &ContributionCalendar,
+        granularity: Granularity,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Self {
+        let mut buckets = vec![];
+        let mut start = granularity.bucket_start(from);
+
+        while start <= to {
+            let end = granularity.bucket_end(start);
+            let (bucket_from, bucket_to) = (start.max(from), end.min(to));
+            let contributions = calendar
+                .within(&DateRange::new(bucket_from, bucket_to))
+                .days()
+                .iter()
+                .map(|d| d.count)
+                .sum();
+
+            buckets.push(Bucket {
+                label: granularity.label(start),
+                from: bucket_from,
+                to: bucket_to,
+                contributions,
+            });
+            start = match end.succ_opt() {
+                Some(next) => next,
+                None => break,
+            };
+        }
+
+        Trend {
+            username: username.to_string(),
+            granularity,
+            buckets,
+        }
+    }
+
+    /// Name of the account.
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    /// Size of the buckets.
+    pub fn granularity(&self) -> Granularity {
+        self.granularity
+    }
+
+    /// Buckets, oldest first.
+    pub fn buckets(&self) -> &[Bucket] {
+        &self.buckets
+    }
+
+    fn max(&self) -> u32 {
+        self.buckets
+            .iter()
+            .map(|b| b.contributions)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// One character per bucket, from `▁` for none to `█` for the busiest bucket.
+    pub fn sparkline(&self) -> String {
+        let max = u64::from(self.max());
+
+        self.buckets
+            .iter()
+            .map(|b| match max {
+                0 => SPARKS[0],
+                max => SPARKS[(u64::from(b.contributions) * 7 / max) as usize],
+            })
+            .collect()
+    }
+}
+
+/// A bar of `value` relative to `max`, [`BAR_WIDTH`] columns at most, in eighths of a column.
+fn bar(value: u32, max: u32) -> String {
+    if max == 0 {
+        return String::new();
+    }
+
+    let eighths =
+        (u64::from(value) * u64::from(BAR_WIDTH) * 8 + u64::from(max) / 2) / u64::from(max);
+    let mut bar = "█".repeat((eighths / 8) as usize);
+    if eighths % 8 > 0 {
+        bar.push(EIGHTHS[(eighths % 8 - 1) as usize]);
+    }
+    bar
+}
+
+impl fmt::Display for Trend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Local sources have no username.
+        match self.username.as_str() {
+            "" => write!(f, "trend ({}", self.granularity)?,
+            username => write!(f, "trend: {} ({}", username, self.granularity)?,
+        }
+        match (self.buckets.first(), self.buckets.last()) {
+            (Some(first), Some(last)) => writeln!(f, ", {}..{})", first.from, last.to)?,
+            _ => writeln!(f, ")")?,
+        }
+        write!(f, "{}", self.sparkline())?;
+
+        let max = self.max();
+        let label_width = self
+            .buckets
+            .iter()
+            .map(|b| b.label.len())
+            .max()
+            .unwrap_or(0);
+        let count_width = max.to_string().len();
+
+        for bucket in &self.buckets {
+            let line = format!(
+                "{:<label_width$}  {:>count_width$}  {}",
+                bucket.label,
+                bucket.contributions,
+                bar(bucket.contributions, max),
+                label_width = label_width,
+                count_width = count_width
+            );
+            write!(f, "\n{}", line.trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{date, sample_calendar};
+
+    #[test]
+    fn test_monthly() {
+        let trend = Trend::new(
+            "k-nasa",
+            &sample_calendar(),
+            Granularity::Monthly,
+            date("2018-01-21"),
+            date("2019-01-26"),
+        );
+        let buckets = trend.buckets();
+
+        assert_eq!(13, buckets.len());
+        assert_eq!("2018-01", buckets[0].label);
+        assert_eq!(date("2018-01-21"), buckets[0].from);
+        assert_eq!(date("2018-01-31"), buckets[0].to);
+        assert_eq!(date("2018-02-28"), buckets[1].to);
+        assert_eq!(
+            Bucket {
+                label: "2019-01".to_string(),
+                from: date("2019-01-01"),
+                to: date("2019-01-26"),
+                contributions: 260,
+            },
+            buckets[12]
+        );
+        assert_eq!(3532, buckets.iter().map(|b| b.contributions).sum::<u32>());
+    }
+
+    #[test]
+    fn test_weekly() {
+        let trend = Trend::new(
+            "k-nasa",
+            &sample_calendar(),
+            Granularity::Weekly,
+            date("2019-01-14"),
+            date("2019-01-26"),
+        );
+
+        assert_eq!(
+            vec!["2019-W03", "2019-W04"],
+            trend
+                .buckets()
+                .iter()
+                .map(|b| b.label.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(date("2019-01-20"), trend.buckets()[0].to);
+        assert_eq!(51, trend.buckets()[1].contributions);
+    }
+
+    #[test]
+    fn test_sparkline_and_bars() {
+        let calendar = ContributionCalendar::new(
+            [0, 2, 4, 8]
+                .iter()
+                .zip(date("2019-01-01").iter_days())
+                .map(|(&count, date)| crate::calendar::ContributionDay {
+                    date,
+                    count,
+                    level: 0,
+                })
+                .collect(),
+        );
+        let trend = Trend::new(
+            "k-nasa",
+            &calendar,
+            Granularity::Daily,
+            date("2019-01-01"),
+            date("2019-01-04"),
+        );
+
+        assert_eq!("▁▂▄█", trend.sparkline());
+        assert_eq!(
+            trend.to_string(),
+            format!(
+                "trend: k-nasa (daily, 2019-01-01..2019-01-04)
+▁▂▄█
+2019-01-01  0
+2019-01-02  2  {}
+2019-01-03  4  {}
+2019-01-04  8  {}",
+                "█".repeat(10),
+                "█".repeat(20),
+                "█".repeat(40)
+            )
+        );
+        assert_eq!("▏", bar(1, 320));
+        assert_eq!("█▌", bar(3, 80));
+    }
+
+    #[test]
+    fn test_empty_calendar() {
+        let trend = Trend::new(
+            "nobody",
+            &ContributionCalendar::default(),
+            Granularity::Weekly,
+            date("2019-01-01"),
+            date("2019-01-07"),
+        );
+
+        assert_eq!("▁▁", trend.sparkline());
+        assert!(trend.to_string().ends_with("2019-W02  0"));
+    }
+}
@@ -0,0 +1,423 @@
+//! Interactive dashboard of one or more users, `contriview tui`.
+//!
+//! The heatmap, summary, period sums and monthly trend of the selected user, with a cursor over
+//! the days of the heatmap.
+
+use crate::calendar::ContributionCalendar;
+use crate::contriview::ContriView;
+use crate::error::{ContriviewError, Result};
+use crate::heatmap::{first_day, rgb, Heatmap, WEEKS};
+use crate::parser::PALETTE;
+use crate::period::{Period, PeriodSummary};
+use crate::trend::{Granularity, Trend};
+use crate::week::WeekMode;
+use chrono::prelude::*;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{BarChart, Block, Paragraph, Sparkline, Tabs};
+use ratatui::Frame;
+
+const CURSOR: &str = "▣";
+const HELP: &str = "←↓↑→ move  tab/shift-tab user  p/P period  q quit";
+
+/// State of the dashboard.
+#[derive(Debug, Clone)]
+pub struct App {
+    users: Vec<(String, ContributionCalendar)>,
+    user: usize,
+    periods: Vec<Period>,
+    period: usize,
+    date: NaiveDate,
+    cursor: NaiveDate,
+    week: WeekMode,
+    quit: bool,
+}
+
+impl App {
+    /// A dashboard of the calendars of `users` as of `date`, with the cursor on `date`.
+    pub fn new(users: Vec<(String, ContributionCalendar)>, date: NaiveDate) -> Self {
+        App {
+            users,
+            user: 0,
+            periods: vec![
+                Period::LastDays(7),
+                Period::LastDays(30),
+                Period::MonthToDate,
+                Period::QuarterToDate,
+                Period::YearToDate,
+            ],
+            period: 0,
+            date,
+            cursor: date,
+            week: WeekMode::default(),
+            quit: false,
+        }
+    }
+
+    /// Cycles through `periods` instead of the default ones.
+    pub fn with_periods(mut self, periods: Vec<Period>) -> Self {
+        if !periods.is_empty() {
+            self.periods = periods;
+            self.period = 0;
+        }
+        self
+    }
+
+    /// Computes the summary with the week selected by `week`.
+    pub fn with_week(mut self, week: WeekMode) -> Self {
+        self.week = week;
+        self
+    }
+
+    /// Name of the selected user.
+    pub fn username(&self) -> &str {
+        &self.users[self.user].0
+    }
+
+    /// The selected period.
+    pub fn period(&self) -> Period {
+        self.periods[self.period]
+    }
+
+    /// Day under the cursor.
+    pub fn cursor(&self) -> NaiveDate {
+        self.cursor
+    }
+
+    /// Returns true once the user asked to leave.
+    pub fn should_quit(&self) -> bool {
+        self.quit
+    }
+
+    fn calendar(&self) -> &ContributionCalendar {
+        &self.users[self.user].1
+    }
+
+    /// Applies a key press. Left and right move by a week, up and down by a day.
+    pub fn handle_key(&mut self, key: KeyCode) {
+        let step = |i: usize, len: usize, forward: bool| {
+            if forward {
+                (i + 1) % len
+            } else {
+                (i + len - 1) % len
+            }
+        };
+
+        match key {
+            KeyCode::Left => self.move_cursor(-7),
+            KeyCode::Right => self.move_cursor(7),
+            KeyCode::Up => self.move_cursor(-1),
+            KeyCode::Down => self.move_cursor(1),
+            KeyCode::Tab => self.user = step(self.user, self.users.len(), true),
+            KeyCode::BackTab => self.user = step(self.user, self.users.len(), false),
+            KeyCode::Char('p') => self.period = step(self.period, self.periods.len(), true),
+            KeyCode::Char('P') => self.period = step(self.period, self.periods.len(), false),
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            _ => {}
+        }
+    }
+
+    /// Moves the cursor by `days`, staying on the heatmap.
+    fn move_cursor(&mut self, days: i64) {
+        let moved = self.cursor + chrono::Duration::days(days);
+        if first_day(self.date) <= moved && moved <= self.date {
+            self.cursor = moved;
+        }
+    }
+
+    /// Draws the dashboard on `frame`.
+    pub fn draw(&self, frame: &mut Frame) {
+        let [tabs, heatmap, bottom, help] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(12),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [stats, right] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(bottom);
+        let [period, trend] =
+            Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(right);
+
+        let titles = self.users.iter().map(|(username, _)| username.as_str());
+        frame.render_widget(
+            Tabs::new(titles)
+                .select(self.user)
+                .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+                .block(Block::bordered().title("users")),
+            tabs,
+        );
+
+        self.draw_heatmap(frame, heatmap);
+
+        let view = ContriView::from_calendar_with_week(self.calendar(), self.date, self.week);
+        frame.render_widget(
+            Paragraph::new(view.to_string()).block(Block::bordered().title("summary")),
+            stats,
+        );
+
+        self.draw_period(frame, period);
+        self.draw_trend(frame, trend);
+
+        frame.render_widget(Paragraph::new(HELP), help);
+    }
+
+    fn draw_heatmap(&self, frame: &mut Frame, area: Rect) {
+        let calendar = self.calendar();
+        let start = first_day(self.date);
+        let mut lines = vec![Line::from(Heatmap::new(calendar, self.date).month_labels())];
+
+        for weekday in 0..7 {
+            let label = match weekday {
+                1 => "Mon ",
+                3 => "Wed ",
+                5 => "Fri ",
+                _ => "    ",
+            };
+            let mut spans = vec![Span::raw(label)];
+
+            for week in 0..WEEKS {
+                let date = start + chrono::Duration::days((week * 7 + weekday) as i64);
+                let day = calendar.get(date).filter(|_| date <= self.date);
+                let (r, g, b) = rgb(PALETTE[day.map_or(0, |d| usize::from(d.level.min(4)))]);
+                let style = Style::default().fg(Color::Rgb(r, g, b));
+
+                let symbol = match day {
+                    _ if date == self.cursor => CURSOR,
+                    Some(_) => "■",
+                    None => " ",
+                };
+                spans.push(Span::styled(symbol, style));
+                spans.push(Span::raw(" "));
+            }
+            lines.push(Line::from(spans));
+        }
+
+        let count = calendar.get(self.cursor).map_or(0, |d| d.count);
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!(
+            "{}: {} contributions",
+            self.cursor, count
+        )));
+
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(self.username())),
+            area,
+        );
+    }
+
+    fn draw_period(&self, frame: &mut Frame, area: Rect) {
+        let period = self.period();
+        let block = Block::bordered().title(format!("period: {}", period));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        let summary = match PeriodSummary::new(self.calendar(), &period, self.date) {
+            Ok(summary) => summary,
+            Err(e) => return frame.render_widget(Paragraph::new(e.to_string()), inner),
+        };
+
+        let [text, chart] =
+            Layout::vertical([Constraint::Length(5), Constraint::Min(0)]).areas(inner);
+        frame.render_widget(
+            Paragraph::new(format!(
+                "{}..{}\nsum: {}\naverage: {:.2}\nactive_days: {}",
+                summary.from, summary.to, summary.sum, summary.average, summary.active_days
+            )),
+            text,
+        );
+
+        // The latest days that fit, one column each.
+        let counts: Vec<u64> = summary
+            .from
+            .iter_days()
+            .take_while(|d| *d <= summary.to)
+            .map(|d| self.calendar().get(d).map_or(0, |d| u64::from(d.count)))
+            .collect();
+        let shown = &counts[counts.len().saturating_sub(usize::from(chart.width))..];
+        frame.render_widget(
+            Sparkline::default()
+                .data(shown)
+                .style(Style::default().fg(Color::Green)),
+            chart,
+        );
+    }
+
+    fn draw_trend(&self, frame: &mut Frame, area: Rect) {
+        let trend = Trend::new(
+            self.username(),
+            self.calendar(),
+            Granularity::Monthly,
+            first_day(self.date),
+            self.date,
+        );
+        // Month numbers, the year is in the heatmap.
+        let bars: Vec<(&str, u64)> = trend
+            .buckets()
+            .iter()
+            .map(|b| (&b.label[5..], u64::from(b.contributions)))
+            .collect();
+
+        frame.render_widget(
+            BarChart::default()
+                .data(&bars)
+                .bar_width(3)
+                .bar_gap(1)
+                .bar_style(Style::default().fg(Color::Green))
+                .block(Block::bordered().title("monthly")),
+            area,
+        );
+    }
+}
+
+/// Runs `app` in the terminal until the user quits.
+pub fn run(mut app: App) -> Result<()> {
+    if app.users.is_empty() {
+        return Err(ContriviewError::Config(
+            "no users to show, give one or define a team".to_string(),
+        ));
+    }
+
+    let mut terminal = ratatui::try_init().map_err(|e| {
+        ratatui::restore();
+        ContriviewError::Terminal(e)
+    })?;
+    let result = (|| -> std::io::Result<()> {
+        while !app.should_quit() {
+            terminal.draw(|frame| app.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    app.handle_key(key.code);
+                }
+            }
+        }
+        Ok(())
+    })();
+    ratatui::restore();
+
+    result.map_err(ContriviewError::Terminal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{date, sample_calendar, sample_html};
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn app() -> App {
+        let calendar = ContributionCalendar::from_html(sample_html()).unwrap();
+        App::new(
+            vec![
+                ("k-nasa".to_string(), calendar),
+                ("nobody".to_string(), ContributionCalendar::default()),
+            ],
+            date("2019-01-26"),
+        )
+    }
+
+    /// Draws `app` and returns the screen, one string per row.
+    fn screen(app: &App) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(130, 45)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
+    fn contains(screen: &[String], text: &str) -> bool {
+        screen.iter().any(|row| row.contains(text))
+    }
+
+    #[test]
+    fn test_draw() {
+        let screen = screen(&app());
+
+        assert!(contains(&screen, "k-nasa"));
+        assert!(contains(&screen, "2019-01-26: 3 contributions"));
+        assert!(contains(&screen, "sum_contributions: 3532"));
+        assert!(contains(
+            &screen,
+            "longest_streak: 50 (2018-09-17..2018-11-05)"
+        ));
+        assert!(contains(&screen, "period: 7d"));
+        assert!(contains(&screen, "sum: 51"));
+        assert!(contains(&screen, "monthly"));
+        assert!(contains(&screen, HELP));
+        assert_eq!(
+            1,
+            screen
+                .iter()
+                .map(|row| row.matches(CURSOR).count())
+                .sum::<usize>()
+        );
+    }
+
+    #[test]
+    fn test_cursor() {
+        let mut app = app();
+
+        app.handle_key(KeyCode::Left);
+        app.handle_key(KeyCode::Up);
+        assert_eq!(date("2019-01-18"), app.cursor());
+        let expected = format!(
+            "2019-01-18: {} contributions",
+            sample_calendar().get(date("2019-01-18")).unwrap().count
+        );
+        assert!(contains(&screen(&app), &expected));
+
+        // The cursor stays within the heatmap.
+        app.handle_key(KeyCode::Right);
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Down);
+        assert_eq!(date("2019-01-26"), app.cursor());
+        for _ in 0..WEEKS {
+            app.handle_key(KeyCode::Left);
+        }
+        assert_eq!(date("2018-01-27"), app.cursor());
+    }
+
+    #[test]
+    fn test_switch_user_and_period() {
+        let mut app = app();
+
+        app.handle_key(KeyCode::Tab);
+        assert_eq!("nobody", app.username());
+        assert!(contains(&screen(&app), "sum_contributions: 0"));
+        app.handle_key(KeyCode::BackTab);
+        assert_eq!("k-nasa", app.username());
+
+        app.handle_key(KeyCode::Char('P'));
+        assert_eq!(Period::YearToDate, app.period());
+        let screen = screen(&app);
+        assert!(contains(&screen, "period: ytd"));
+        assert!(contains(&screen, "sum: 260"));
+
+        assert!(!app.should_quit());
+        app.handle_key(KeyCode::Char('q'));
+        assert!(app.should_quit());
+    }
+
+    #[test]
+    fn test_given_periods() {
+        let mut app = app().with_periods(vec![Period::LastDays(7), Period::MonthToDate]);
+        assert_eq!(Period::LastDays(7), app.period());
+        assert!(contains(&screen(&app), "sum: 51"));
+
+        app.handle_key(KeyCode::Char('p'));
+        assert_eq!(Period::MonthToDate, app.period());
+        app.handle_key(KeyCode::Char('p'));
+        assert_eq!(Period::LastDays(7), app.period());
+
+        let app = app.with_periods(vec![Period::LastDays(u32::MAX)]);
+        assert!(contains(&screen(&app), "invalid period"));
+    }
+}
@@ -0,0 +1,84 @@
+//! Which days count as "the week".
+
+use chrono::prelude::*;
+
+/// The seven days summed into `week_contributions`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WeekMode {
+    /// The seven days ending on the target date.
+    #[default]
+    Rolling,
+    /// The calendar week containing the target date, starting on the given day: Monday for
+    /// ISO weeks, Sunday for Sunday to Saturday weeks.
+    Calendar(Weekday),
+    /// Week `n` of the ISO year of the target date, Monday to Sunday.
+    IsoWeek(u32),
+}
+
+impl WeekMode {
+    /// Monday to Sunday weeks.
+    pub const ISO: WeekMode = WeekMode::Calendar(Weekday::Mon);
+
+    /// First and last day of the week for the target `date`, or `None` if the ISO year has no
+    /// such week.
+    pub fn window(&self, date: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+        let from = match *self {
+            WeekMode::Rolling => date - chrono::Duration::days(6),
+            WeekMode::Calendar(start) => {
+                let since_start =
+                    (date.weekday().num_days_from_monday() + 7 - start.num_days_from_monday()) % 7;
+                date - chrono::Duration::days(i64::from(since_start))
+            }
+            WeekMode::IsoWeek(week) => {
+                NaiveDate::from_isoywd_opt(date.iso_week().year(), week, Weekday::Mon)?
+            }
+        };
+
+        Some((from, from + chrono::Duration::days(6)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::date;
+
+    // 2019-01-26 is a Saturday.
+
+    #[test]
+    fn test_rolling() {
+        assert_eq!(
+            Some((date("2019-01-20"), date("2019-01-26"))),
+            WeekMode::Rolling.window(date("2019-01-26"))
+        );
+    }
+
+    #[test]
+    fn test_calendar() {
+        assert_eq!(
+            Some((date("2019-01-21"), date("2019-01-27"))),
+            WeekMode::ISO.window(date("2019-01-26"))
+        );
+        assert_eq!(
+            Some((date("2019-01-20"), date("2019-01-26"))),
+            WeekMode::Calendar(Weekday::Sun).window(date("2019-01-26"))
+        );
+        assert_eq!(
+            Some((date("2019-01-27"), date("2019-02-02"))),
+            WeekMode::Calendar(Weekday::Sun).window(date("2019-01-27"))
+        );
+    }
+
+    #[test]
+    fn test_iso_week() {
+        assert_eq!(
+            Some((date("2018-12-31"), date("2019-01-06"))),
+            WeekMode::IsoWeek(1).window(date("2019-01-26"))
+        );
+        assert_eq!(
+            Some((date("2020-12-28"), date("2021-01-03"))),
+            WeekMode::IsoWeek(53).window(date("2020-06-01"))
+        );
+        assert_eq!(None, WeekMode::IsoWeek(53).window(date("2019-01-26")));
+    }
+}